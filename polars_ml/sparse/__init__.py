@@ -1,3 +1,27 @@
 from polars_ml.sparse.sparse_namespace import SparseNamespace
-from polars_ml.sparse.functions import from_list, get, normalize
+from polars_ml.sparse.functions import (from_list, get, gather, normalize, to_dense, add, sub, multiply, maximum,
+                                        minimum, dot, cosine_similarity, sum_, mean, nnz, min_, max_, norm, arg_max, arg_min,
+                                        select_indices, slice_, canonicalize, validate, from_coo, to_coo,
+                                        explode, hash_features, matmul, to_csr,
+                                        parse_libsvm, format_libsvm,
+                                        fit_max_abs_scaler, fit_standard_scaler, fit_min_max_scaler, scale,
+                                        fit_normalize, fit_idf, tfidf, top_k, threshold,
+                                        remap, concat, fit_one_hot, one_hot, fit_multi_hot, multi_hot,
+                                        to_compact, from_compact)
+from polars_ml.sparse.io import to_scipy, read_libsvm, write_libsvm
 
+
+# names of builtins are defined with a trailing underscore, so functions.py does not shadow them
+sum = sum_
+min = min_
+max = max_
+slice = slice_
+
+# the aliases of builtins are left out, so a star import does not shadow the builtins of the caller
+__all__ = ['SparseNamespace', 'from_list', 'get', 'gather', 'normalize', 'to_dense', 'add', 'sub', 'multiply',
+           'maximum', 'minimum', 'dot', 'cosine_similarity', 'mean', 'nnz', 'norm', 'arg_max', 'arg_min',
+           'select_indices', 'canonicalize', 'validate', 'from_coo', 'to_coo', 'explode', 'hash_features', 'matmul',
+           'to_csr', 'to_scipy', 'parse_libsvm', 'format_libsvm', 'read_libsvm', 'write_libsvm', 'fit_max_abs_scaler',
+           'fit_standard_scaler', 'fit_min_max_scaler', 'scale', 'fit_normalize', 'fit_idf', 'tfidf', 'top_k',
+           'threshold', 'remap', 'concat', 'fit_one_hot', 'one_hot', 'fit_multi_hot', 'multi_hot', 'to_compact',
+           'from_compact']
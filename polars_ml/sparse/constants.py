@@ -1,3 +1,5 @@
 DIM = 'dim'
 INDICES = 'indices'
 VALUES = 'values'
+INDEX = 'index'
+VALUE = 'value'
@@ -1,43 +1,602 @@
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple, Union
 import polars as pl
 from polars.plugins import register_plugin_function
 from polars_ml import lib
-from polars_ml.sparse.constants import DIM, INDICES, VALUES
 
 
-def from_list(expr: pl.Expr) -> pl.expr:
-    output_name = expr.meta.output_name()
-    return pl.struct(pl.when(expr.is_null()).then(None).otherwise(expr.list.len()).alias(DIM),
-                     expr.list.eval(pl.arg_where(pl.element() != 0)).alias(INDICES),
-                     expr.list.eval(pl.element().filter(pl.element() != 0)).alias(VALUES)).alias(output_name)
+def from_list(expr: pl.Expr, *, epsilon: float = 0.0) -> pl.Expr:
+    # validate params
+    if epsilon < 0.0:
+        raise ValueError(f'epsilon must be greater or equals to 0.0, {epsilon} was given.')
 
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='from_list',
+        is_elementwise=True,
+        kwargs={'epsilon': epsilon}
+    )
 
-def get(expr: pl.Expr, index: int) -> pl.expr:
-    # validate index is non-negative
-    if index < 0:
-        raise Exception('index must be non-negative value')
 
-    output_name = expr.meta.output_name()
-    return pl.when(expr.is_null()).then(None) \
-        .when(expr.struct.field(DIM) <= index).then(None) \
-        .when(expr.struct.field(INDICES).list.contains(index)) \
-        .then(
-            expr.struct.field(VALUES).list.get(
-                expr.struct.field(INDICES).list.eval(pl.arg_where(pl.element() == index)).list.get(0, null_on_oob=True)
-        )
-    ).otherwise(0).alias(output_name)
+def get(expr: pl.Expr, index: Union[int, pl.Expr]) -> pl.Expr:
+    if isinstance(index, int):
+        # validate index is non-negative
+        if index < 0:
+            raise Exception('index must be non-negative value')
+        index = pl.lit(index, dtype=pl.UInt32)
+
+    return register_plugin_function(
+        args=[expr, index],
+        plugin_path=lib,
+        function_name='get',
+        is_elementwise=True
+    )
+
 
+def gather(expr: pl.Expr, indices: List[int]) -> pl.Expr:
+    # validate indices are non-negative
+    if any(index < 0 for index in indices):
+        raise Exception('indices must be non-negative values')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='gather',
+        is_elementwise=True,
+        kwargs={'indices': list(indices)}
+    )
 
-def normalize(expr: pl.Expr,  *, how: str = 'vertical', p: float = 2.0) -> pl.Expr:
+
+def normalize(expr: pl.Expr,  *, how: str = 'vertical', p: float = 2.0,
+              fitted: Union[pl.Expr, pl.Series, pl.DataFrame] = None, unseen: str = 'ignore') -> pl.Expr:
     # validate params
-    if how not in ['vertical']:
-        raise ValueError(f'Illegal how = {how}, only vertical is supported.')
+    if how not in ['vertical', 'horizontal']:
+        raise ValueError(f'Illegal how = {how}, only vertical and horizontal are supported.')
     if p < 1.0:
-        raise ValueError(f'p must be greater or equals to 1.0, {p} was given.')
+        raise ValueError(f'p must be greater or equals to 1.0 (or float("inf") for max-norm), {p} was given.')
+
+    # norms fitted on another frame, see fit_normalize
+    if fitted is not None:
+        if how != 'vertical':
+            raise ValueError('fitted is only supported for vertical normalization.')
+        return scale(expr, fitted, unseen=unseen)
+
+    # vertical normalization is a fit/scale pair, since its norms are aggregated over the whole column
+    if how == 'vertical':
+        return scale(expr, fit_normalize(expr, p=p))
 
     return register_plugin_function(
         args=[expr],
         plugin_path=lib,
         function_name='normalize',
         is_elementwise=True,
-        kwargs={'how': how, 'p': p}
+        kwargs={'p': p}
+    )
+
+
+def to_dense(expr: pl.Expr, *, dim: int = None) -> pl.Expr:
+    # validate params
+    if dim is not None and dim < 0:
+        raise ValueError(f'dim must be None or non-negative, {dim} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='to_dense',
+        is_elementwise=True,
+        kwargs={'dim': dim}
+    )
+
+
+def _binary(expr: pl.Expr, other: pl.Expr, function_name: str) -> pl.Expr:
+    return register_plugin_function(
+        args=[expr, other],
+        plugin_path=lib,
+        function_name=function_name,
+        is_elementwise=True
+    )
+
+
+def add(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'add')
+
+
+def sub(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'sub')
+
+
+def multiply(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'multiply')
+
+
+def maximum(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'maximum')
+
+
+def minimum(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'minimum')
+
+
+def dot(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'dot')
+
+
+def cosine_similarity(expr: pl.Expr, other: pl.Expr) -> pl.Expr:
+    return _binary(expr, other, 'cosine_similarity')
+
+
+def _summarize(expr: pl.Expr, stat: str, how: str, p: float = 2.0) -> pl.Expr:
+    # validate params
+    if how not in ['vertical', 'horizontal']:
+        raise ValueError(f'Illegal how = {how}, only vertical and horizontal are supported.')
+
+    # vertical reduces the whole column to a single sparse vector of per index stats, as normalize does,
+    # horizontal reduces every row to a scalar
+    if how == 'horizontal':
+        return register_plugin_function(
+            args=[expr],
+            plugin_path=lib,
+            function_name='summarize_horizontal',
+            is_elementwise=True,
+            kwargs={'stat': stat, 'p': p}
+        )
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='summarize',
+        is_elementwise=False,
+        returns_scalar=True,
+        kwargs={'stat': stat, 'p': p}
+    )
+
+
+def sum_(expr: pl.Expr, *, how: str = 'vertical') -> pl.Expr:
+    return _summarize(expr, 'sum', how)
+
+
+def mean(expr: pl.Expr, *, how: str = 'vertical') -> pl.Expr:
+    return _summarize(expr, 'mean', how)
+
+
+def nnz(expr: pl.Expr, *, how: str = 'vertical') -> pl.Expr:
+    return _summarize(expr, 'nnz', how)
+
+
+def min_(expr: pl.Expr, *, how: str = 'vertical') -> pl.Expr:
+    return _summarize(expr, 'min', how)
+
+
+def max_(expr: pl.Expr, *, how: str = 'vertical') -> pl.Expr:
+    return _summarize(expr, 'max', how)
+
+
+def norm(expr: pl.Expr, *, how: str = 'vertical', p: float = 2.0) -> pl.Expr:
+    # validate params
+    if p < 1.0:
+        raise ValueError(f'p must be greater or equals to 1.0 (or float("inf") for max-norm), {p} was given.')
+
+    return _summarize(expr, 'norm', how, p)
+
+
+def arg_max(expr: pl.Expr) -> pl.Expr:
+    return _summarize(expr, 'arg_max', 'horizontal')
+
+
+def arg_min(expr: pl.Expr) -> pl.Expr:
+    return _summarize(expr, 'arg_min', 'horizontal')
+
+
+def select_indices(expr: pl.Expr, keep: List[int], *, reindex: bool = False) -> pl.Expr:
+    # validate indices are non-negative
+    if any(index < 0 for index in keep):
+        raise Exception('keep must contain non-negative values')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='select_indices',
+        is_elementwise=True,
+        kwargs={'keep': list(keep), 'reindex': reindex}
+    )
+
+
+def slice_(expr: pl.Expr, start: int, end: int = None) -> pl.Expr:
+    # validate params
+    if start < 0:
+        raise ValueError(f'start must be non-negative, {start} was given.')
+    if end is not None and end < start:
+        raise ValueError(f'end must be None or greater or equals to start, {end} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='slice',
+        is_elementwise=True,
+        kwargs={'start': start, 'end': end}
+    )
+
+
+def canonicalize(expr: pl.Expr, *, dup_policy: str = 'sum') -> pl.Expr:
+    # validate params
+    if dup_policy not in ['sum', 'max', 'last']:
+        raise ValueError(f'Illegal dup_policy = {dup_policy}, only sum, max and last are supported.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='canonicalize',
+        is_elementwise=True,
+        kwargs={'dup_policy': dup_policy}
+    )
+
+
+def validate(expr: pl.Expr, *, strict: bool = False) -> pl.Expr:
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='validate',
+        is_elementwise=True,
+        kwargs={'strict': strict}
+    )
+
+
+def from_coo(indices: pl.Expr, values: pl.Expr, dim: int) -> pl.Expr:
+    # validate params
+    if dim < 0:
+        raise ValueError(f'dim must be non-negative, {dim} was given.')
+
+    return register_plugin_function(
+        args=[indices, values],
+        plugin_path=lib,
+        function_name='from_coo',
+        is_elementwise=False,
+        returns_scalar=True,
+        kwargs={'dim': dim}
+    )
+
+
+def to_coo(expr: pl.Expr) -> pl.Expr:
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='to_coo',
+        is_elementwise=True
+    )
+
+
+def explode(expr: pl.Expr) -> pl.Expr:
+    return to_coo(expr).explode()
+
+
+def hash_features(expr: pl.Expr, *, n_features: int = 2 ** 20, alternate_sign: bool = True,
+                  seed: int = 0) -> pl.Expr:
+    # validate params
+    if n_features <= 0 or n_features >= 2 ** 32:
+        raise ValueError(f'n_features must be between 1 and 2 ** 32 - 1, {n_features} was given.')
+    if seed < 0 or seed >= 2 ** 32:
+        raise ValueError(f'seed must be between 0 and 2 ** 32 - 1, {seed} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='hash_features',
+        is_elementwise=True,
+        kwargs={'n_features': n_features, 'alternate_sign': alternate_sign, 'seed': seed}
+    )
+
+
+def _weights_to_expr(weights: Any) -> Tuple[pl.Expr, Optional[int]]:
+    import numpy as np
+
+    # weights given as a file are loaded with numpy
+    if isinstance(weights, (str, Path)):
+        weights = np.load(weights)
+    if isinstance(weights, pl.Series):
+        weights = np.stack(weights.to_list()) if weights.dtype in (pl.List, pl.Array) else weights.to_numpy()
+    weights = np.asarray(weights, dtype=np.float64)
+
+    if weights.ndim not in [1, 2]:
+        raise ValueError(f'weights must be a vector or a matrix, {weights.ndim} dimensions were given.')
+    k = None if weights.ndim == 1 else weights.shape[1]
+    flat_weights = pl.Series([weights.ravel(order='C')], dtype=pl.List(pl.Float64))
+    return pl.lit(flat_weights), k
+
+
+def matmul(expr: pl.Expr, weights: Any, *, k: int = None) -> pl.Expr:
+    # weights expressions hold a row-major flattened dim x k matrix, other weights are converted to one
+    if not isinstance(weights, pl.Expr):
+        weights, weights_k = _weights_to_expr(weights)
+        if k is not None and k != weights_k:
+            shape = 'a weight vector' if weights_k is None else f'a weight matrix of {weights_k} columns'
+            raise ValueError(f'k = {k} was given for {shape}.')
+        k = weights_k
+    if k is not None and k <= 0:
+        raise ValueError(f'k must be None or greater than zero, {k} was given.')
+
+    return register_plugin_function(
+        args=[expr, weights],
+        plugin_path=lib,
+        function_name='matmul',
+        is_elementwise=True,
+        kwargs={'k': k}
+    )
+
+
+def to_csr(expr: pl.Expr) -> pl.Expr:
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='to_csr',
+        is_elementwise=False,
+        returns_scalar=True
+    )
+
+
+def parse_libsvm(expr: pl.Expr, *, zero_based: bool = False, n_features: int = None) -> pl.Expr:
+    # validate params
+    if n_features is not None and n_features < 0:
+        raise ValueError(f'n_features must be None or non-negative, {n_features} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='parse_libsvm',
+        is_elementwise=False,
+        kwargs={'zero_based': zero_based, 'n_features': n_features}
+    )
+
+
+def format_libsvm(label: pl.Expr, features: pl.Expr, qid: pl.Expr = None, *, zero_based: bool = False) -> pl.Expr:
+    func_args = [label, features] if qid is None else [label, features, qid]
+    return register_plugin_function(
+        args=func_args,
+        plugin_path=lib,
+        function_name='format_libsvm',
+        is_elementwise=True,
+        kwargs={'zero_based': zero_based}
+    )
+
+
+def fit_max_abs_scaler(expr: pl.Expr) -> pl.Expr:
+    return pl.struct(norm(expr, p=float('inf')).alias('scale'), nnz(expr).alias('nnz'))
+
+
+def fit_standard_scaler(expr: pl.Expr) -> pl.Expr:
+    return pl.struct(_summarize(expr, 'std', 'vertical').alias('scale'), nnz(expr).alias('nnz'))
+
+
+def fit_min_max_scaler(expr: pl.Expr) -> pl.Expr:
+    stored_min = _summarize(expr, 'stored_min', 'vertical')
+    stored_max = _summarize(expr, 'stored_max', 'vertical')
+    return pl.struct(sub(stored_max, stored_min).alias('scale'), stored_min.alias('offset'), nnz(expr).alias('nnz'))
+
+
+def fit_normalize(expr: pl.Expr, *, p: float = 2.0) -> pl.Expr:
+    # validate params
+    if p < 1.0:
+        raise ValueError(f'p must be greater or equals to 1.0 (or float("inf") for max-norm), {p} was given.')
+
+    return pl.struct(norm(expr, p=p).alias('scale'), nnz(expr).alias('nnz'))
+
+
+def _fitted_to_expr(fitted: Union[pl.Expr, pl.Series, pl.DataFrame]) -> pl.Expr:
+    # fitted state computed on another frame is passed as a single row literal
+    if isinstance(fitted, pl.DataFrame):
+        fitted = fitted.to_series()
+    if isinstance(fitted, pl.Series):
+        fitted = pl.lit(fitted)
+    return fitted
+
+
+def scale(expr: pl.Expr, fitted: Union[pl.Expr, pl.Series, pl.DataFrame], *, unseen: str = 'ignore') -> pl.Expr:
+    # validate params
+    if unseen not in ['ignore', 'drop', 'error']:
+        raise ValueError(f'Illegal unseen = {unseen}, only ignore, drop and error are supported.')
+
+    # state fitted on the same frame is an aggregation, so it must see every row of the group before applying
+    is_elementwise = not isinstance(fitted, pl.Expr)
+
+    return register_plugin_function(
+        args=[expr, _fitted_to_expr(fitted)],
+        plugin_path=lib,
+        function_name='scale',
+        is_elementwise=is_elementwise,
+        kwargs={'unseen': unseen}
+    )
+
+
+def fit_idf(expr: pl.Expr, *, smooth_idf: bool = True) -> pl.Expr:
+    idf = register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='fit_idf',
+        is_elementwise=False,
+        returns_scalar=True,
+        kwargs={'smooth_idf': smooth_idf}
+    )
+    return pl.struct(idf.alias('idf'))
+
+
+def tfidf(expr: pl.Expr, *, norm: Optional[str] = 'l2', use_idf: bool = True, smooth_idf: bool = True,
+          sublinear_tf: bool = False, fitted: Union[pl.Expr, pl.Series, pl.DataFrame] = None) -> pl.Expr:
+    # validate params
+    if norm not in ['l1', 'l2', None]:
+        raise ValueError(f'Illegal norm = {norm}, only l1, l2 and None are supported.')
+
+    # idf is fitted on the column itself, unless a state fitted on another frame is given
+    args = [expr]
+    if use_idf:
+        if fitted is None:
+            fitted = fit_idf(expr, smooth_idf=smooth_idf)
+        args.append(_fitted_to_expr(fitted))
+
+    out = register_plugin_function(
+        args=args,
+        plugin_path=lib,
+        function_name='tfidf',
+        is_elementwise=not (use_idf and isinstance(fitted, pl.Expr)),
+        kwargs={'sublinear_tf': sublinear_tf}
+    )
+    if norm is not None:
+        out = normalize(out, how='horizontal', p=1.0 if norm == 'l1' else 2.0)
+    return out
+
+
+def top_k(expr: pl.Expr, k: int, *, by: str = 'abs') -> pl.Expr:
+    # validate params
+    if k < 0:
+        raise ValueError(f'k must be non-negative, {k} was given.')
+    if by not in ['abs', 'value']:
+        raise ValueError(f'Illegal by = {by}, only abs and value are supported.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='top_k',
+        is_elementwise=True,
+        kwargs={'k': k, 'by': by}
+    )
+
+
+def threshold(expr: pl.Expr, min_abs: float) -> pl.Expr:
+    # validate params
+    if min_abs < 0:
+        raise ValueError(f'min_abs must be non-negative, {min_abs} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='threshold',
+        is_elementwise=True,
+        kwargs={'min_abs': min_abs}
+    )
+
+
+def remap(expr: pl.Expr, mapping: Union[Dict[int, int], pl.DataFrame], *, dim: int = None,
+          unmapped: str = 'drop') -> pl.Expr:
+    # validate params
+    if unmapped not in ['drop', 'error']:
+        raise ValueError(f'Illegal unmapped = {unmapped}, only drop and error are supported.')
+
+    # a mapping frame holds the old indices in its first column and the new indices in its second
+    if isinstance(mapping, pl.DataFrame):
+        if mapping.width != 2:
+            raise ValueError(f'mapping must have exactly 2 columns (old, new), {mapping.width} were given.')
+        old, new = mapping.to_series(0).to_list(), mapping.to_series(1).to_list()
+    else:
+        old, new = list(mapping.keys()), list(mapping.values())
+    if any(index is None or index < 0 for index in old + new):
+        raise ValueError('mapping must contain non-negative indices.')
+    if dim is None:
+        dim = max(new, default=-1) + 1
+    if any(index >= dim for index in new):
+        raise ValueError(f'mapping contains new indices out of bounds for dim = {dim}.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='remap',
+        is_elementwise=True,
+        kwargs={'old': old, 'new': new, 'dim': dim, 'unmapped': unmapped}
+    )
+
+
+def concat(exprs: List[pl.Expr], *, dims: Optional[List[Optional[int]]] = None) -> pl.Expr:
+    # validate params
+    if len(exprs) == 0:
+        raise ValueError('exprs must contain at least one sparse column.')
+    if dims is None:
+        dims = [None] * len(exprs)
+    if len(dims) != len(exprs):
+        raise ValueError(f'dims must contain a dim per sparse column, {len(dims)} were given for {len(exprs)} columns.')
+    if any(dim is not None and dim < 0 for dim in dims):
+        raise ValueError('dims must contain None or non-negative dims.')
+
+    # a block without a dim takes it from its compact schema, or else from its rows
+    return register_plugin_function(
+        args=list(exprs),
+        plugin_path=lib,
+        function_name='concat',
+        is_elementwise=True,
+        kwargs={'dims': list(dims)}
+    )
+
+
+def _fit_vocabulary(expr: pl.Expr, min_frequency: int, max_categories: Optional[int]) -> pl.Expr:
+    # validate params
+    if min_frequency < 1:
+        raise ValueError(f'min_frequency must be positive, {min_frequency} was given.')
+    if max_categories is not None and max_categories < 0:
+        raise ValueError(f'max_categories must be None or non-negative, {max_categories} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='fit_vocabulary',
+        is_elementwise=False,
+        returns_scalar=True,
+        kwargs={'min_frequency': min_frequency, 'max_categories': max_categories}
+    )
+
+
+def _hot_encode(expr: pl.Expr, vocabulary: Union[pl.Expr, pl.Series, pl.DataFrame, List[str]],
+                min_frequency: int, max_categories: Optional[int], unknown: str) -> pl.Expr:
+    # validate params
+    if unknown not in ['bucket', 'ignore', 'error']:
+        raise ValueError(f'Illegal unknown = {unknown}, only bucket, ignore and error are supported.')
+
+    # the vocabulary is fitted on the column itself, unless one fitted on another frame is given
+    if vocabulary is None:
+        vocabulary = _fit_vocabulary(expr, min_frequency, max_categories)
+    elif isinstance(vocabulary, list):
+        vocabulary = pl.Series([vocabulary], dtype=pl.List(pl.String))
+
+    return register_plugin_function(
+        args=[expr, _fitted_to_expr(vocabulary)],
+        plugin_path=lib,
+        function_name='hot_encode',
+        is_elementwise=not isinstance(vocabulary, pl.Expr),
+        kwargs={'unknown': unknown}
+    )
+
+
+def fit_one_hot(expr: pl.Expr, *, min_frequency: int = 1, max_categories: int = None) -> pl.Expr:
+    return _fit_vocabulary(expr.cast(pl.String), min_frequency, max_categories)
+
+
+def one_hot(expr: pl.Expr, *, vocabulary: Union[pl.Expr, pl.Series, pl.DataFrame, List[str]] = None,
+            min_frequency: int = 1, max_categories: int = None, unknown: str = 'bucket') -> pl.Expr:
+    return _hot_encode(expr.cast(pl.String), vocabulary, min_frequency, max_categories, unknown)
+
+
+def fit_multi_hot(expr: pl.Expr, *, min_frequency: int = 1, max_categories: int = None) -> pl.Expr:
+    return _fit_vocabulary(expr.cast(pl.List(pl.String)), min_frequency, max_categories)
+
+
+def multi_hot(expr: pl.Expr, *, vocabulary: Union[pl.Expr, pl.Series, pl.DataFrame, List[str]] = None,
+              min_frequency: int = 1, max_categories: int = None, unknown: str = 'bucket') -> pl.Expr:
+    return _hot_encode(expr.cast(pl.List(pl.String)), vocabulary, min_frequency, max_categories, unknown)
+
+
+def to_compact(expr: pl.Expr, dim: int) -> pl.Expr:
+    # validate params
+    if dim < 0:
+        raise ValueError(f'dim must be non-negative, {dim} was given.')
+
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='to_compact',
+        is_elementwise=True,
+        kwargs={'dim': dim}
+    )
+
+
+def from_compact(expr: pl.Expr) -> pl.Expr:
+    return register_plugin_function(
+        args=[expr],
+        plugin_path=lib,
+        function_name='from_compact',
+        is_elementwise=True
     )
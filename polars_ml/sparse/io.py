@@ -0,0 +1,35 @@
+from pathlib import Path
+from typing import Any, Union
+import polars as pl
+from polars_ml.sparse.functions import to_csr, parse_libsvm, format_libsvm
+
+
+def to_scipy(series: pl.Series) -> Any:
+    from scipy.sparse import csr_matrix
+
+    csr = pl.select(to_csr(pl.lit(series))).to_series().struct.unnest()
+    dim = csr['dim'][0]
+    if dim is None:
+        raise ValueError('can not convert a column without non-null sparse vectors.')
+    indptr = csr['indptr'][0].to_numpy()
+    indices = csr['indices'][0].to_numpy()
+    data = csr['data'][0].to_numpy()
+    return csr_matrix((data, indices, indptr), shape=(len(series), dim))
+
+
+def read_libsvm(path: Union[str, Path], *, zero_based: bool = False, n_features: int = None,
+                query_id: bool = False) -> pl.DataFrame:
+    lines = pl.DataFrame({'line': Path(path).read_text().splitlines()}, schema={'line': pl.String})
+    df = lines.select(parse_libsvm(pl.col('line'), zero_based=zero_based, n_features=n_features)) \
+        .unnest('line') \
+        .filter(pl.col('label').is_not_null())
+    return df if query_id else df.drop('qid')
+
+
+def write_libsvm(df: pl.DataFrame, path: Union[str, Path], *, label: str = 'label', features: str = 'features',
+                 query_id: str = None, zero_based: bool = False) -> None:
+    qid = None if query_id is None else pl.col(query_id)
+    lines = df.select(format_libsvm(pl.col(label), pl.col(features), qid, zero_based=zero_based)).to_series()
+    if lines.null_count() > 0:
+        raise ValueError('can not write rows with a null label or null features.')
+    Path(path).write_text(''.join(f'{line}\n' for line in lines))
@@ -3,12 +3,24 @@ import polars as pl
 from polars_ml.sparse import functions
 
 
+BUILTIN_NAMES = {'sum', 'min', 'max', 'slice'}
+# functions whose first argument is not a single column of sparse vectors
+NON_METHOD_NAMES = {'from_coo', 'format_libsvm', 'concat'}
+
+
 @pl.api.register_expr_namespace("sparse")
 class SparseNamespace:
     def __init__(self, expr: pl.Expr):
         self._expr = expr
 
     def __getattr__(self, function_name: str) -> Callable[[Any], pl.Expr]:
+        if function_name in NON_METHOD_NAMES:
+            raise AttributeError(f'{function_name} is not supported in the sparse namespace, '
+                                 f'use polars_ml.sparse.{function_name} instead.')
+        # functions named after builtins are defined with a trailing underscore
+        if function_name in BUILTIN_NAMES:
+            function_name = f'{function_name}_'
+
         def func(*args: Any, **kwargs: Any) -> pl.Expr:
             return getattr(functions, function_name)(
                 self._expr, *args, **kwargs
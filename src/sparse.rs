@@ -1,6 +1,147 @@
 mod from_list;
 mod normalize;
+mod to_dense;
+mod arithmetic;
+mod similarity;
+mod get;
+mod aggregate;
+mod reduce;
+mod select;
+mod canonicalize;
+mod coo;
+mod hashing;
+mod matmul;
+mod csr;
+mod libsvm;
+mod scale;
+mod tfidf;
+mod prune;
+mod remap;
+mod concat;
+mod encode;
+mod compact;
+
+use polars::prelude::*;
 
 pub const DIM: &str = "dim";
 pub const INDICES: &str = "indices";
 pub const VALUES: &str = "values";
+pub const INDEX: &str = "index";
+pub const VALUE: &str = "value";
+
+/// Splits a sparse vector column into its dim, indices and values fields, in either the regular or compact layout.
+pub(crate) fn sparse_fields(s: &Series) -> PolarsResult<(IdxCa, ListChunked, ListChunked)> {
+    let struct_ = s.struct_()?;
+    let fields = struct_.fields();
+
+    // compact sparse vectors carry a single dim in the name of their indices field
+    if let Some(dim) = sparse_schema_dim(s.dtype()) {
+        let indices_ca = fields[0].cast(&DataType::List(Box::new(IDX_DTYPE)))?.list()?.clone();
+        let dim_ca: IdxCa = indices_ca.is_not_null().into_iter()
+            .map(|is_valid| is_valid.unwrap_or(false).then_some(dim))
+            .collect_ca("");
+        let values_ca = fields[1].list()?.clone();
+        return Ok((dim_ca, indices_ca, values_ca));
+    }
+
+    polars_ensure!(fields.len() == 3, ComputeError: "expected a sparse vector struct with fields \
+        '{}', '{}' and '{}', or a compact one with fields '{}[<dim>]' and '{}'.", DIM, INDICES, VALUES, INDICES, VALUES);
+    let dim_ca = fields[0].cast(&IDX_DTYPE)?.idx()?.clone();
+    let indices_ca = fields[1].cast(&DataType::List(Box::new(IDX_DTYPE)))?.list()?.clone();
+    let values_ca = fields[2].list()?.clone();
+    Ok((dim_ca, indices_ca, values_ca))
+}
+
+/// Returns the values dtype of a sparse vector field.
+pub(crate) fn sparse_values_dtype(field: &Field) -> PolarsResult<DataType> {
+    match field.data_type() {
+        // values are the last field of both the regular and the compact layout
+        DataType::Struct(fields) if fields.len() == 3 || sparse_schema_dim(field.data_type()).is_some() => {
+            match fields.last().unwrap().data_type() {
+                DataType::List(element) => Ok(*element.clone()),
+                dtype => polars_bail!(ComputeError: "expected '{}' to be a list, got {}.", VALUES, dtype),
+            }
+        },
+        dtype => polars_bail!(ComputeError: "expected a sparse vector struct, got {}.", dtype),
+    }
+}
+
+/// Boolean values are an implicit 1 when true, kernels keeping the values dtype handle them as UInt8.
+pub(crate) fn numeric_values(values_ca: &ListChunked) -> PolarsResult<ListChunked> {
+    match values_ca.inner_dtype() {
+        DataType::Boolean => Ok(values_ca.cast(&DataType::List(Box::new(DataType::UInt8)))?.list()?.clone()),
+        _ => Ok(values_ca.clone()),
+    }
+}
+
+/// Casts values computed on numeric_values back to the original values dtype.
+pub(crate) fn restore_values(values_ca: ListChunked, values_dtype: &DataType) -> PolarsResult<ListChunked> {
+    match values_dtype {
+        DataType::Boolean => Ok(values_ca.cast(&DataType::List(Box::new(DataType::Boolean)))?.list()?.clone()),
+        _ => Ok(values_ca),
+    }
+}
+
+/// Returns the sparse vector struct dtype for the given values dtype.
+pub(crate) fn sparse_dtype(values_dtype: DataType) -> DataType {
+    DataType::Struct(vec![Field::new(DIM, IDX_DTYPE),
+                          Field::new(INDICES, DataType::List(Box::new(IDX_DTYPE))),
+                          Field::new(VALUES, DataType::List(Box::new(values_dtype)))])
+}
+
+/// Returns the field of a function producing Float64 sparse vectors.
+pub(crate) fn float_sparse_vector(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(input_fields[0].name(), sparse_dtype(DataType::Float64)))
+}
+
+/// Returns the field of a function keeping the values dtype of its sparse vector input.
+pub(crate) fn same_sparse_vector(input_fields: &[Field]) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    Ok(Field::new(field.name(), sparse_dtype(sparse_values_dtype(field)?)))
+}
+
+/// Returns the name of the indices field of a compact sparse vector of the given dim.
+pub(crate) fn compact_indices_name(dim: IdxSize) -> String {
+    format!("{}[{}]", INDICES, dim)
+}
+
+/// Returns the dim carried in the schema of a compact sparse vector, or None for any other dtype.
+pub(crate) fn sparse_schema_dim(dtype: &DataType) -> Option<IdxSize> {
+    match dtype {
+        DataType::Struct(fields) if fields.len() == 2 => fields[0].name()
+            .strip_prefix(INDICES)?
+            .strip_prefix('[')?
+            .strip_suffix(']')?
+            .parse()
+            .ok(),
+        _ => None,
+    }
+}
+
+/// Fails at plan time when compact sparse vectors of different dims are combined.
+pub(crate) fn check_schema_dims(input_fields: &[Field]) -> PolarsResult<()> {
+    let mut dims = input_fields.iter().filter_map(|field| sparse_schema_dim(field.data_type()));
+    if let Some(first) = dims.next() {
+        for dim in dims {
+            polars_ensure!(dim == first, ShapeMismatch: "sparse vectors of dims {} and {} can not be combined.", first, dim);
+        }
+    }
+    Ok(())
+}
+
+/// Assembles a sparse vector column from its dim, indices and values fields.
+pub(crate) fn sparse_struct(name: &str, dim_ca: IdxCa, indices_ca: ListChunked, values_ca: ListChunked) -> PolarsResult<Series> {
+    let out = StructChunked::new(name, &[dim_ca.with_name(DIM).into_series(),
+                                         indices_ca.with_name(INDICES).into_series(),
+                                         values_ca.with_name(VALUES).into_series()])?;
+    Ok(out.into_series())
+}
+
+/// Broadcasts a unit length column, such as a literal, to the given length.
+pub(crate) fn broadcast(s: &Series, len: usize) -> PolarsResult<Series> {
+    match s.len() {
+        n if n == len => Ok(s.clone()),
+        1 => Ok(s.new_from_index(0, len)),
+        n => polars_bail!(ShapeMismatch: "expected a column of length {} or 1, got {}.", len, n),
+    }
+}
@@ -0,0 +1,178 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use std::collections::HashMap;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{sparse_dtype, sparse_fields, sparse_struct, sparse_values_dtype};
+
+
+#[derive(Deserialize)]
+struct SummarizeKwargs {
+    stat: String,
+    p: f64,
+}
+
+/// Statistics of the stored values of a single index.
+pub(crate) struct IndexSummary {
+    pub nnz: IdxSize,
+    pub sum: f64,
+    pub sum_squares: f64,
+    pub min: f64,
+    pub max: f64,
+    pub norm: f64,
+}
+
+/// Per index statistics of a sparse vector column.
+pub(crate) struct SparseSummary {
+    pub dim: Option<IdxSize>,
+    pub n_rows: IdxSize,
+    pub indices: HashMap<IdxSize, IndexSummary>,
+}
+
+impl SparseSummary {
+    // min and max account for the implicit zeros of rows not storing the index
+    fn min(&self, summary: &IndexSummary) -> f64 {
+        if summary.nnz < self.n_rows { summary.min.min(0.0) } else { summary.min }
+    }
+
+    fn max(&self, summary: &IndexSummary) -> f64 {
+        if summary.nnz < self.n_rows { summary.max.max(0.0) } else { summary.max }
+    }
+
+    // population standard deviation, implicit zeros included
+    fn std(&self, summary: &IndexSummary) -> f64 {
+        let n_rows = self.n_rows as f64;
+        let mean = summary.sum / n_rows;
+        (summary.sum_squares / n_rows - mean * mean).max(0.0).sqrt()
+    }
+}
+
+fn summary_sparse_vector(input_fields: &[Field], kwargs: SummarizeKwargs) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    let values_dtype = match kwargs.stat.as_str() {
+        "nnz" => IDX_DTYPE,
+        "min" | "max" | "stored_min" | "stored_max" => sparse_values_dtype(field)?,
+        _ => DataType::Float64,
+    };
+    Ok(Field::new(field.name(), sparse_dtype(values_dtype)))
+}
+
+#[polars_expr(output_type_func_with_kwargs=summary_sparse_vector)]
+fn summarize(inputs: &[Series], kwargs: SummarizeKwargs) -> PolarsResult<Series> {
+    let stat = kwargs.stat.as_str();
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let summary = sparse_summarize(&dim_ca, &indices_ca, &values_ca, kwargs.p)?;
+
+    // reduce the column into a single sparse vector, sorted by index
+    let mut indices: Vec<IdxSize> = summary.indices.keys().copied().collect();
+    indices.sort_unstable();
+    let values: Vec<f64> = indices.iter().map(|idx| {
+        let index_summary = &summary.indices[idx];
+        match stat {
+            "sum" => Ok(index_summary.sum),
+            "mean" => Ok(index_summary.sum / summary.n_rows as f64),
+            "nnz" => Ok(index_summary.nnz as f64),
+            "min" => Ok(summary.min(index_summary)),
+            "max" => Ok(summary.max(index_summary)),
+            "stored_min" => Ok(index_summary.min),
+            "stored_max" => Ok(index_summary.max),
+            "std" => Ok(summary.std(index_summary)),
+            "norm" => Ok(index_summary.norm),
+            stat => polars_bail!(ComputeError: "'{}' is unsupported.", stat),
+        }
+    }).collect::<PolarsResult<_>>()?;
+
+    // drop indices whose statistic is zero, to keep the result sparse
+    let (indices, values): (Vec<IdxSize>, Vec<f64>) = indices.into_iter().zip(values)
+        .filter(|(_, value)| *value != 0.0)
+        .unzip();
+
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", 1, indices.len(), IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new("", 1, values.len(), DataType::Float64);
+    let out_dim_ca: IdxCa = match summary.dim {
+        Some(dim) => {
+            indices_builder.append_slice(&indices);
+            values_builder.append_slice(&values);
+            [Some(dim)].into_iter().collect_ca("")
+        },
+        None => {
+            indices_builder.append_null();
+            values_builder.append_null();
+            [None].into_iter().collect_ca("")
+        }
+    };
+    let out_values_dtype = match stat {
+        "nnz" => IDX_DTYPE,
+        "min" | "max" | "stored_min" | "stored_max" => values_ca.inner_dtype().clone(),
+        _ => DataType::Float64,
+    };
+    let out_values_ca = values_builder.finish().cast(&DataType::List(Box::new(out_values_dtype)))?;
+    sparse_struct(inputs[0].name(), out_dim_ca, indices_builder.finish(), out_values_ca.list()?.clone())
+}
+
+/// Summarizes the values stored under every index, with the Lp norm computed for the given p.
+pub(crate) fn sparse_summarize(dim_ca: &IdxCa, indices_list_ca: &ListChunked, values_list_ca: &ListChunked, p: f64) -> PolarsResult<SparseSummary> {
+    let values_list_ca = values_list_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let values_list_ca = values_list_ca.list()?;
+    let mut summary = SparseSummary {
+        dim: None,
+        n_rows: 0,
+        indices: HashMap::new(),
+    };
+    unsafe {
+        for ((dim, indices_series), values_series) in dim_ca.into_iter()
+            .zip(indices_list_ca.amortized_iter())
+            .zip(values_list_ca.amortized_iter()) {
+            if let (Some(indices_series), Some(values_series)) = (indices_series, values_series) {
+                let indices_ca = indices_series.as_ref().idx()?;
+                let values_ca = values_series.as_ref().f64()?;
+                // every row of the column shares the same dim, as in to_csr
+                match (summary.dim, dim) {
+                    (Some(summary_dim), Some(dim)) => polars_ensure!(summary_dim == dim, ComputeError: "sparse \
+                        vectors dims are inconsistent, got {} and {}.", summary_dim, dim),
+                    (None, dim) => summary.dim = dim,
+                    _ => {}
+                }
+                summary.n_rows += 1;
+                add_single_list_to_summary(indices_ca, values_ca, &mut summary.indices, p);
+            }
+        }
+    }
+
+    // max-norm is already accumulated as the max absolute value
+    if !p.is_infinite() {
+        let exp = 1.0 / p;
+        summary.indices.values_mut().for_each(|index_summary| index_summary.norm = index_summary.norm.powf(exp));
+    }
+    Ok(summary)
+}
+
+#[inline]
+fn add_single_list_to_summary(indices_ca: &IdxCa, values_ca: &Float64Chunked,
+                              indices_summaries: &mut HashMap<IdxSize, IndexSummary>, p: f64) -> ()
+{
+    indices_ca.iter().zip(values_ca.iter())
+        .for_each(|(idx, value)| {
+            let idx = idx.unwrap();
+            let value = value.unwrap();
+            let index_summary = indices_summaries.entry(idx).or_insert(IndexSummary {
+                nnz: 0,
+                sum: 0.0,
+                sum_squares: 0.0,
+                min: f64::INFINITY,
+                max: f64::NEG_INFINITY,
+                norm: 0.0,
+            });
+            index_summary.nnz += 1;
+            index_summary.sum += value;
+            index_summary.sum_squares += value * value;
+            index_summary.min = index_summary.min.min(value);
+            index_summary.max = index_summary.max.max(value);
+            index_summary.norm = if p.is_infinite() {
+                index_summary.norm.max(value.abs())
+            } else {
+                index_summary.norm + value.abs().powf(p)
+            };
+        });
+}
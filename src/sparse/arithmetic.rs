@@ -0,0 +1,255 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use num_traits::Zero;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use pyo3_polars::export::polars_core::utils::try_get_supertype;
+use crate::sparse::{broadcast, check_schema_dims, sparse_dtype, sparse_fields, sparse_struct, sparse_values_dtype};
+
+
+#[derive(Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Multiply,
+    Maximum,
+    Minimum,
+}
+
+impl BinaryOp {
+    // ops whose result is zero whenever one side is zero only need the intersection of the indices
+    fn is_intersection(&self) -> bool {
+        matches!(self, BinaryOp::Multiply)
+    }
+
+    // integers wrap around on overflow, as they do in polars' own arithmetic kernels
+    #[inline]
+    fn apply<N: NumericNative + WrappingOps>(&self, left: N, right: N) -> N {
+        match self {
+            BinaryOp::Add => left.wrapping_add(right),
+            BinaryOp::Sub => left.wrapping_sub(right),
+            BinaryOp::Multiply => left.wrapping_mul(right),
+            BinaryOp::Maximum => if left >= right { left } else { right },
+            BinaryOp::Minimum => if left <= right { left } else { right },
+        }
+    }
+}
+
+/// Arithmetic that wraps around on integer overflow instead of panicking.
+trait WrappingOps {
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+}
+
+macro_rules! impl_wrapping_ops {
+    ($($native:ty),*) => {
+        $(impl WrappingOps for $native {
+            #[inline]
+            fn wrapping_add(self, other: Self) -> Self { <$native>::wrapping_add(self, other) }
+            #[inline]
+            fn wrapping_sub(self, other: Self) -> Self { <$native>::wrapping_sub(self, other) }
+            #[inline]
+            fn wrapping_mul(self, other: Self) -> Self { <$native>::wrapping_mul(self, other) }
+        })*
+    };
+}
+
+impl_wrapping_ops!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+// floats have no overflow to wrap, so they use the plain operators
+macro_rules! impl_float_wrapping_ops {
+    ($($native:ty),*) => {
+        $(impl WrappingOps for $native {
+            #[inline]
+            fn wrapping_add(self, other: Self) -> Self { self + other }
+            #[inline]
+            fn wrapping_sub(self, other: Self) -> Self { self - other }
+            #[inline]
+            fn wrapping_mul(self, other: Self) -> Self { self * other }
+        })*
+    };
+}
+
+impl_float_wrapping_ops!(f32, f64);
+
+/// Promotes both values dtypes to their supertype, the same way polars does, with Boolean values as UInt8 ones.
+fn values_supertype(left: &DataType, right: &DataType) -> PolarsResult<DataType> {
+    let promote = |dtype: &DataType| match dtype {
+        DataType::Boolean => DataType::UInt8,
+        dtype => dtype.clone(),
+    };
+    try_get_supertype(&promote(left), &promote(right))
+}
+
+fn supertype_sparse_vector(input_fields: &[Field]) -> PolarsResult<Field> {
+    check_schema_dims(input_fields)?;
+    let left_dtype = sparse_values_dtype(&input_fields[0])?;
+    let right_dtype = sparse_values_dtype(&input_fields[1])?;
+    Ok(Field::new(input_fields[0].name(), sparse_dtype(values_supertype(&left_dtype, &right_dtype)?)))
+}
+
+#[polars_expr(output_type_func=supertype_sparse_vector)]
+fn add(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_binary(inputs, BinaryOp::Add)
+}
+
+#[polars_expr(output_type_func=supertype_sparse_vector)]
+fn sub(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_binary(inputs, BinaryOp::Sub)
+}
+
+#[polars_expr(output_type_func=supertype_sparse_vector)]
+fn multiply(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_binary(inputs, BinaryOp::Multiply)
+}
+
+#[polars_expr(output_type_func=supertype_sparse_vector)]
+fn maximum(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_binary(inputs, BinaryOp::Maximum)
+}
+
+#[polars_expr(output_type_func=supertype_sparse_vector)]
+fn minimum(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_binary(inputs, BinaryOp::Minimum)
+}
+
+fn impl_binary(inputs: &[Series], op: BinaryOp) -> PolarsResult<Series> {
+    let len = inputs[0].len().max(inputs[1].len());
+    let (left_dim_ca, left_indices_ca, left_values_ca) = sparse_fields(&broadcast(&inputs[0], len)?)?;
+    let (right_dim_ca, right_indices_ca, right_values_ca) = sparse_fields(&broadcast(&inputs[1], len)?)?;
+
+    let values_dtype = values_supertype(&left_values_ca.inner_dtype(), &right_values_ca.inner_dtype())?;
+    let list_dtype = DataType::List(Box::new(values_dtype.clone()));
+    let left_values_ca = left_values_ca.cast(&list_dtype)?.list()?.clone();
+    let right_values_ca = right_values_ca.cast(&list_dtype)?.list()?.clone();
+
+    let left = (&left_dim_ca, &left_indices_ca, &left_values_ca);
+    let right = (&right_dim_ca, &right_indices_ca, &right_values_ca);
+    let (dim_ca, indices_ca, values_ca) = match values_dtype {
+        DataType::Int8 => impl_binary_ca::<Int8Type>(left, right, op)?,
+        DataType::Int16 => impl_binary_ca::<Int16Type>(left, right, op)?,
+        DataType::Int32 => impl_binary_ca::<Int32Type>(left, right, op)?,
+        DataType::Int64 => impl_binary_ca::<Int64Type>(left, right, op)?,
+        DataType::UInt8 => impl_binary_ca::<UInt8Type>(left, right, op)?,
+        DataType::UInt16 => impl_binary_ca::<UInt16Type>(left, right, op)?,
+        DataType::UInt32 => impl_binary_ca::<UInt32Type>(left, right, op)?,
+        DataType::UInt64 => impl_binary_ca::<UInt64Type>(left, right, op)?,
+        DataType::Float32 => impl_binary_ca::<Float32Type>(left, right, op)?,
+        DataType::Float64 => impl_binary_ca::<Float64Type>(left, right, op)?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for sparse arithmetic, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64."))
+        }
+    };
+    sparse_struct(inputs[0].name(), dim_ca, indices_ca, values_ca)
+}
+
+fn impl_binary_ca<T>(left: (&IdxCa, &ListChunked, &ListChunked),
+                     right: (&IdxCa, &ListChunked, &ListChunked),
+                     op: BinaryOp) -> PolarsResult<(IdxCa, ListChunked, ListChunked)>
+    where
+        T: PolarsNumericType,
+        T::Native: WrappingOps,
+{
+    let (left_dim_ca, left_indices_ca, left_values_ca) = left;
+    let (right_dim_ca, right_indices_ca, right_values_ca) = right;
+    let len = left_dim_ca.len();
+    let mut dim_vec: Vec<Option<IdxSize>> = Vec::with_capacity(len);
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<T> = ListPrimitiveChunkedBuilder::new("", len, len, T::get_dtype());
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<T::Native> = Vec::new();
+
+    unsafe {
+        let left_iter = left_dim_ca.into_iter()
+            .zip(left_indices_ca.amortized_iter())
+            .zip(left_values_ca.amortized_iter());
+        let right_iter = right_dim_ca.into_iter()
+            .zip(right_indices_ca.amortized_iter())
+            .zip(right_values_ca.amortized_iter());
+        for (((left_dim, left_indices), left_values), ((right_dim, right_indices), right_values)) in left_iter.zip(right_iter) {
+            match (left_dim, left_indices, left_values, right_dim, right_indices, right_values) {
+                (Some(left_dim), Some(left_indices), Some(left_values),
+                    Some(right_dim), Some(right_indices), Some(right_values)) => {
+                    polars_ensure!(left_dim == right_dim, ComputeError: "sparse vectors dims differ, \
+                        got {} and {}.", left_dim, right_dim);
+                    out_indices.clear();
+                    out_values.clear();
+                    merge_sparse::<T>(
+                        left_indices.as_ref().idx()?, left_values.as_ref().unpack::<T>()?,
+                        right_indices.as_ref().idx()?, right_values.as_ref().unpack::<T>()?,
+                        op, &mut out_indices, &mut out_values);
+                    dim_vec.push(Some(left_dim));
+                    indices_builder.append_slice(&out_indices);
+                    values_builder.append_slice(&out_values);
+                },
+                _ => {
+                    dim_vec.push(None);
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    let dim_ca: IdxCa = dim_vec.into_iter().collect_ca("");
+    Ok((dim_ca, indices_builder.finish(), values_builder.finish()))
+}
+
+/// Merges two sparse vectors with sorted indices, skipping zero results.
+#[inline]
+fn merge_sparse<T>(left_indices: &IdxCa, left_values: &ChunkedArray<T>,
+                   right_indices: &IdxCa, right_values: &ChunkedArray<T>,
+                   op: BinaryOp, out_indices: &mut Vec<IdxSize>, out_values: &mut Vec<T::Native>)
+    where
+        T: PolarsNumericType,
+        T::Native: WrappingOps,
+{
+    let zero = T::Native::zero();
+    let mut left = left_indices.into_no_null_iter().zip(left_values.into_no_null_iter()).peekable();
+    let mut right = right_indices.into_no_null_iter().zip(right_values.into_no_null_iter()).peekable();
+    let mut push = |idx: IdxSize, value: T::Native| {
+        if value != zero {
+            out_indices.push(idx);
+            out_values.push(value);
+        }
+    };
+
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (Some((left_idx, left_value)), Some((right_idx, right_value))) => {
+                if left_idx == right_idx {
+                    push(left_idx, op.apply(left_value, right_value));
+                    left.next();
+                    right.next();
+                } else if left_idx < right_idx {
+                    if !op.is_intersection() {
+                        push(left_idx, op.apply(left_value, zero));
+                    }
+                    left.next();
+                } else {
+                    if !op.is_intersection() {
+                        push(right_idx, op.apply(zero, right_value));
+                    }
+                    right.next();
+                }
+            },
+            (Some((left_idx, left_value)), None) => {
+                if op.is_intersection() {
+                    break;
+                }
+                push(left_idx, op.apply(left_value, zero));
+                left.next();
+            },
+            (None, Some((right_idx, right_value))) => {
+                if op.is_intersection() {
+                    break;
+                }
+                push(right_idx, op.apply(zero, right_value));
+                right.next();
+            },
+            (None, None) => break,
+        }
+    }
+}
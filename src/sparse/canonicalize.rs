@@ -0,0 +1,203 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use num_traits::Zero;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{numeric_values, restore_values, same_sparse_vector, sparse_fields, sparse_struct};
+
+
+#[derive(Deserialize)]
+struct CanonicalizeKwargs {
+    dup_policy: String,
+}
+
+#[derive(Deserialize)]
+struct ValidateKwargs {
+    strict: bool,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum DupPolicy {
+    Sum,
+    Max,
+    Last,
+}
+
+impl DupPolicy {
+    pub(crate) fn parse(dup_policy: &str) -> PolarsResult<Self> {
+        match dup_policy {
+            "sum" => Ok(DupPolicy::Sum),
+            "max" => Ok(DupPolicy::Max),
+            "last" => Ok(DupPolicy::Last),
+            dup_policy => polars_bail!(ComputeError: "'{}' is unsupported.", dup_policy),
+        }
+    }
+
+    /// Boolean values are summed as a logical or, so duplicated trues stay true.
+    pub(crate) fn for_dtype(self, values_dtype: &DataType) -> Self {
+        match (self, values_dtype) {
+            (DupPolicy::Sum, DataType::Boolean) => DupPolicy::Max,
+            (dup_policy, _) => dup_policy,
+        }
+    }
+
+    #[inline]
+    fn combine<N: NumericNative>(&self, old: N, new: N) -> N {
+        match self {
+            DupPolicy::Sum => old + new,
+            DupPolicy::Max => if new > old { new } else { old },
+            DupPolicy::Last => new,
+        }
+    }
+}
+
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn canonicalize(inputs: &[Series], kwargs: CanonicalizeKwargs) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let values_dtype = values_ca.inner_dtype();
+    let dup_policy = DupPolicy::parse(&kwargs.dup_policy)?.for_dtype(&values_dtype);
+    let values_ca = numeric_values(&values_ca)?;
+
+    let (indices_ca, values_ca) = match values_ca.inner_dtype() {
+        DataType::Int8 => impl_canonicalize::<Int8Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::Int16 => impl_canonicalize::<Int16Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::Int32 => impl_canonicalize::<Int32Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::Int64 => impl_canonicalize::<Int64Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::UInt8 => impl_canonicalize::<UInt8Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::UInt16 => impl_canonicalize::<UInt16Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::UInt32 => impl_canonicalize::<UInt32Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::UInt64 => impl_canonicalize::<UInt64Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::Float32 => impl_canonicalize::<Float32Type>(&indices_ca, &values_ca, dup_policy)?,
+        DataType::Float64 => impl_canonicalize::<Float64Type>(&indices_ca, &values_ca, dup_policy)?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for canonicalize, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
+        }
+    };
+    sparse_struct(inputs[0].name(), dim_ca, indices_ca, restore_values(values_ca, &values_dtype)?)
+}
+
+#[polars_expr(output_type=Boolean)]
+fn validate(inputs: &[Series], kwargs: ValidateKwargs) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+
+    let mut out: Vec<Option<bool>> = Vec::with_capacity(dim_ca.len());
+    unsafe {
+        for (row, ((dim, indices), values)) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter())
+            .enumerate() {
+            match (dim, indices, values) {
+                (Some(dim), Some(indices), Some(values)) => {
+                    let indices = indices.as_ref().idx()?;
+                    let error = validate_row(dim, indices, values.as_ref());
+                    if let (Some(error), true) = (&error, kwargs.strict) {
+                        polars_bail!(ComputeError: "invalid sparse vector at row {}: {}.", row, error);
+                    }
+                    out.push(Some(error.is_none()));
+                },
+                _ => out.push(None)
+            }
+        }
+    }
+
+    let out: BooleanChunked = out.into_iter().collect_ca(inputs[0].name());
+    Ok(out.into_series())
+}
+
+/// Describes the first violation of the canonical form in a row, if any.
+fn validate_row(dim: IdxSize, indices: &IdxCa, values: &Series) -> Option<String> {
+    if indices.len() != values.len() {
+        return Some(format!("{} indices but {} values", indices.len(), values.len()));
+    }
+    if indices.null_count() > 0 {
+        return Some("null index".to_string());
+    }
+    let mut previous: Option<IdxSize> = None;
+    for idx in indices.into_no_null_iter() {
+        if idx >= dim {
+            return Some(format!("index {} is out of bounds for dim {}", idx, dim));
+        }
+        match previous {
+            Some(previous) if previous == idx => return Some(format!("index {} is duplicated", idx)),
+            Some(previous) if previous > idx => return Some(format!("index {} follows index {}, \
+                indices are not sorted", idx, previous)),
+            _ => previous = Some(idx),
+        }
+    }
+    None
+}
+
+fn impl_canonicalize<T>(indices_ca: &ListChunked, values_ca: &ListChunked, dup_policy: DupPolicy) -> PolarsResult<(ListChunked, ListChunked)>
+    where
+        T: PolarsNumericType,
+{
+    let len = indices_ca.len();
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<T> = ListPrimitiveChunkedBuilder::new("", len, len, T::get_dtype());
+    let mut pairs: Vec<(IdxSize, T::Native)> = Vec::new();
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<T::Native> = Vec::new();
+
+    unsafe {
+        for (indices, values) in indices_ca.amortized_iter().zip(values_ca.amortized_iter()) {
+            match (indices, values) {
+                (Some(indices), Some(values)) => {
+                    let indices = indices.as_ref().idx()?;
+                    let values = values.as_ref().unpack::<T>()?;
+                    polars_ensure!(indices.len() == values.len(), ComputeError: "sparse vector has {} \
+                        indices but {} values.", indices.len(), values.len());
+                    pairs.clear();
+                    pairs.extend(indices.into_iter().zip(values)
+                        .filter_map(|(idx, value)| Some((idx?, value?))));
+                    canonicalize_pairs::<T>(&mut pairs, dup_policy, &mut out_indices, &mut out_values);
+                    indices_builder.append_slice(&out_indices);
+                    values_builder.append_slice(&out_values);
+                },
+                _ => {
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    Ok((indices_builder.finish(), values_builder.finish()))
+}
+
+/// Sorts (index, value) pairs by index, combines duplicated indices and drops explicit zeros.
+#[inline]
+pub(crate) fn canonicalize_pairs<T>(pairs: &mut [(IdxSize, T::Native)], dup_policy: DupPolicy,
+                                    out_indices: &mut Vec<IdxSize>, out_values: &mut Vec<T::Native>)
+    where
+        T: PolarsNumericType,
+{
+    // stable sort, so the last duplicate is the last one given
+    pairs.sort_by_key(|(idx, _)| *idx);
+    out_indices.clear();
+    out_values.clear();
+    for (idx, value) in pairs.iter() {
+        match out_indices.last() {
+            Some(last_idx) if last_idx == idx => {
+                let last_value = out_values.last_mut().unwrap();
+                *last_value = dup_policy.combine(*last_value, *value);
+            },
+            _ => {
+                out_indices.push(*idx);
+                out_values.push(*value);
+            }
+        }
+    }
+
+    let mut position = 0;
+    for read in 0..out_indices.len() {
+        if !out_values[read].is_zero() {
+            out_indices[position] = out_indices[read];
+            out_values[position] = out_values[read];
+            position += 1;
+        }
+    }
+    out_indices.truncate(position);
+    out_values.truncate(position);
+}
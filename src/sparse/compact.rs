@@ -0,0 +1,47 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{compact_indices_name, same_sparse_vector, sparse_fields, sparse_schema_dim, sparse_struct, sparse_values_dtype, VALUES};
+
+
+#[derive(Deserialize)]
+struct ToCompactKwargs {
+    dim: u32,
+}
+
+fn compact_sparse_vector(input_fields: &[Field], kwargs: ToCompactKwargs) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    if let Some(schema_dim) = sparse_schema_dim(field.data_type()) {
+        polars_ensure!(schema_dim <= kwargs.dim, ShapeMismatch: "sparse vector of dim {} can not be \
+            compacted to dim {}.", schema_dim, kwargs.dim);
+    }
+    Ok(Field::new(field.name(),
+                  DataType::Struct(vec![Field::new(&compact_indices_name(kwargs.dim), DataType::List(Box::new(IDX_DTYPE))),
+                                        Field::new(VALUES, DataType::List(Box::new(sparse_values_dtype(field)?)))])))
+}
+
+/// Drops the per row dim, carrying the given dim in the name of the indices field instead.
+#[polars_expr(output_type_func_with_kwargs=compact_sparse_vector)]
+fn to_compact(inputs: &[Series], kwargs: ToCompactKwargs) -> PolarsResult<Series> {
+    let dim = kwargs.dim as IdxSize;
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+
+    // rows of a lower dim are padded, since their indices are valid in the larger space too
+    for row_dim in dim_ca.into_iter().flatten() {
+        polars_ensure!(row_dim <= dim, ComputeError: "sparse vector of dim {} can not be \
+            compacted to dim {}.", row_dim, dim);
+    }
+
+    let out = StructChunked::new(inputs[0].name(),
+                                 &[indices_ca.with_name(&compact_indices_name(dim)).into_series(),
+                                   values_ca.with_name(VALUES).into_series()])?;
+    Ok(out.into_series())
+}
+
+/// Restores the regular layout, with the dim of the schema repeated on every row.
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn from_compact(inputs: &[Series]) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    sparse_struct(inputs[0].name(), dim_ca, indices_ca, values_ca)
+}
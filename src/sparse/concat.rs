@@ -0,0 +1,104 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use polars::chunked_array::builder::get_list_builder;
+use pyo3_polars::derive::polars_expr;
+use pyo3_polars::export::polars_core::utils::try_get_supertype;
+use serde::Deserialize;
+use crate::sparse::{broadcast, sparse_dtype, sparse_fields, sparse_schema_dim, sparse_struct, sparse_values_dtype};
+
+
+#[derive(Deserialize)]
+struct ConcatKwargs {
+    dims: Vec<Option<u32>>,
+}
+
+fn concat_sparse_vector(input_fields: &[Field], kwargs: ConcatKwargs) -> PolarsResult<Field> {
+    polars_ensure!(kwargs.dims.len() == input_fields.len(), ComputeError: "expected {} dims, got {}.",
+        input_fields.len(), kwargs.dims.len());
+    for (block, (field, dim)) in input_fields.iter().zip(&kwargs.dims).enumerate() {
+        if let (Some(schema_dim), Some(dim)) = (sparse_schema_dim(field.data_type()), dim) {
+            polars_ensure!(schema_dim == *dim, ShapeMismatch: "block {} of dim {} can not be \
+                concatenated as dim {}.", block, schema_dim, dim);
+        }
+    }
+    let mut values_dtype = sparse_values_dtype(&input_fields[0])?;
+    for field in &input_fields[1..] {
+        values_dtype = try_get_supertype(&values_dtype, &sparse_values_dtype(field)?)?;
+    }
+    Ok(Field::new(input_fields[0].name(), sparse_dtype(values_dtype)))
+}
+
+/// Stacks the sparse vectors of every block side by side, offsetting indices by the dims of the blocks before.
+#[polars_expr(output_type_func_with_kwargs=concat_sparse_vector)]
+fn concat(inputs: &[Series], kwargs: ConcatKwargs) -> PolarsResult<Series> {
+    let len = inputs.iter().map(|s| s.len()).max().unwrap_or(0);
+    let blocks = inputs.iter()
+        .map(|s| sparse_fields(&broadcast(s, len)?))
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    // promote all blocks to a common values dtype, the same way polars does
+    let mut values_dtype = blocks[0].2.inner_dtype();
+    for (_, _, values_ca) in &blocks[1..] {
+        values_dtype = try_get_supertype(&values_dtype, &values_ca.inner_dtype())?;
+    }
+    let list_dtype = DataType::List(Box::new(values_dtype.clone()));
+    let values_cas = blocks.iter()
+        .map(|(_, _, values_ca)| Ok(values_ca.cast(&list_dtype)?.list()?.clone()))
+        .collect::<PolarsResult<Vec<ListChunked>>>()?;
+
+    // null rows are all-zero, so every block needs a single dim that does not depend on the batch
+    let block_dims = inputs.iter().zip(&blocks).zip(&kwargs.dims).enumerate()
+        .map(|(block, ((s, (dim_ca, _, _)), dim))| block_dim(block, s, dim_ca, *dim))
+        .collect::<PolarsResult<Vec<IdxSize>>>()?;
+
+    let mut dim_vec: Vec<Option<IdxSize>> = Vec::with_capacity(len);
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder = get_list_builder(&values_dtype, len, len, "")?;
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+
+    unsafe {
+        let mut dim_iters: Vec<_> = blocks.iter().map(|(dim_ca, _, _)| dim_ca.into_iter()).collect();
+        let mut indices_iters: Vec<_> = blocks.iter().map(|(_, indices_ca, _)| indices_ca.amortized_iter()).collect();
+        let mut values_iters: Vec<_> = values_cas.iter().map(|values_ca| values_ca.amortized_iter()).collect();
+
+        for _ in 0..len {
+            let mut offset: IdxSize = 0;
+            let mut out_values = Series::new_empty("", &values_dtype);
+            out_indices.clear();
+            for block in 0..blocks.len() {
+                let dim = dim_iters[block].next().unwrap();
+                let indices = indices_iters[block].next().unwrap();
+                let values = values_iters[block].next().unwrap();
+                if let (Some(_), Some(indices), Some(values)) = (dim, indices, values) {
+                    out_indices.extend(indices.as_ref().idx()?.into_no_null_iter().map(|idx| idx + offset));
+                    out_values.append(values.as_ref())?;
+                }
+                offset += block_dims[block];
+            }
+            dim_vec.push(Some(offset));
+            indices_builder.append_slice(&out_indices);
+            values_builder.append_series(&out_values)?;
+        }
+    }
+
+    let dim_ca: IdxCa = dim_vec.into_iter().collect_ca("");
+    sparse_struct(inputs[0].name(), dim_ca, indices_builder.finish(), values_builder.finish())
+}
+
+/// Resolves the dim of a block from the given dims, then from its schema, and only then from its rows.
+fn block_dim(block: usize, s: &Series, dim_ca: &IdxCa, dim: Option<u32>) -> PolarsResult<IdxSize> {
+    let dim = match dim.map(|dim| dim as IdxSize).or_else(|| sparse_schema_dim(s.dtype())) {
+        Some(dim) => dim,
+        None => match dim_ca.into_iter().flatten().next() {
+            Some(dim) => dim,
+            None => polars_bail!(ComputeError: "block {} has no non-null rows to infer its dim from, \
+                dims must be given.", block),
+        },
+    };
+    for row_dim in dim_ca.into_iter().flatten() {
+        polars_ensure!(row_dim == dim, ComputeError: "sparse vectors dims of block {} are \
+            inconsistent, got {} and {}.", block, dim, row_dim);
+    }
+    Ok(dim)
+}
@@ -0,0 +1,111 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use polars::chunked_array::builder::get_list_builder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{restore_values, sparse_dtype, sparse_fields, sparse_struct, sparse_values_dtype, INDEX, VALUE};
+use crate::sparse::canonicalize::{canonicalize_pairs, DupPolicy};
+
+
+#[derive(Deserialize)]
+struct FromCooKwargs {
+    dim: u32,
+}
+
+fn coo_sparse_vector(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(input_fields[0].name(), sparse_dtype(input_fields[1].data_type().clone())))
+}
+
+fn coo_entries(input_fields: &[Field]) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    let entry_dtype = DataType::Struct(vec![Field::new(INDEX, IDX_DTYPE),
+                                            Field::new(VALUE, sparse_values_dtype(field)?)]);
+    Ok(Field::new(field.name(), DataType::List(Box::new(entry_dtype))))
+}
+
+#[polars_expr(output_type_func=coo_sparse_vector)]
+fn from_coo(inputs: &[Series], kwargs: FromCooKwargs) -> PolarsResult<Series> {
+    polars_ensure!(inputs[0].len() == inputs[1].len(), ShapeMismatch: "indices and values must have \
+        the same length, got {} and {}.", inputs[0].len(), inputs[1].len());
+    let indices_series = inputs[0].cast(&IDX_DTYPE)?;
+    let indices_ca = indices_series.idx()?;
+    polars_ensure!(indices_ca.null_count() == inputs[0].null_count(), ComputeError: "indices must be \
+        non-negative.");
+
+    // duplicated Boolean values are summed as a logical or
+    let values_dtype = inputs[1].dtype();
+    let (values, dup_policy) = match values_dtype {
+        DataType::Boolean => (inputs[1].cast(&DataType::UInt8)?, DupPolicy::Max),
+        _ => (inputs[1].clone(), DupPolicy::Sum),
+    };
+    let (dim_ca, indices_ca, values_ca) = match values.dtype() {
+        DataType::Int8 => impl_from_coo(indices_ca, values.i8()?, kwargs.dim, dup_policy)?,
+        DataType::Int16 => impl_from_coo(indices_ca, values.i16()?, kwargs.dim, dup_policy)?,
+        DataType::Int32 => impl_from_coo(indices_ca, values.i32()?, kwargs.dim, dup_policy)?,
+        DataType::Int64 => impl_from_coo(indices_ca, values.i64()?, kwargs.dim, dup_policy)?,
+        DataType::UInt8 => impl_from_coo(indices_ca, values.u8()?, kwargs.dim, dup_policy)?,
+        DataType::UInt16 => impl_from_coo(indices_ca, values.u16()?, kwargs.dim, dup_policy)?,
+        DataType::UInt32 => impl_from_coo(indices_ca, values.u32()?, kwargs.dim, dup_policy)?,
+        DataType::UInt64 => impl_from_coo(indices_ca, values.u64()?, kwargs.dim, dup_policy)?,
+        DataType::Float32 => impl_from_coo(indices_ca, values.f32()?, kwargs.dim, dup_policy)?,
+        DataType::Float64 => impl_from_coo(indices_ca, values.f64()?, kwargs.dim, dup_policy)?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for from_coo, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
+        }
+    };
+    sparse_struct(inputs[0].name(), dim_ca, indices_ca, restore_values(values_ca, values_dtype)?)
+}
+
+#[polars_expr(output_type_func=coo_entries)]
+fn to_coo(inputs: &[Series]) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let values_dtype = values_ca.inner_dtype().clone();
+    let entry_dtype = DataType::Struct(vec![Field::new(INDEX, IDX_DTYPE),
+                                            Field::new(VALUE, values_dtype)]);
+
+    let mut entries_builder = get_list_builder(&entry_dtype, indices_ca.get_inner().len(), dim_ca.len(), inputs[0].name())?;
+    unsafe {
+        for ((dim, indices), values) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter()) {
+            match (dim, indices, values) {
+                (Some(_), Some(indices), Some(values)) => {
+                    let entries = StructChunked::new("", &[indices.as_ref().clone().with_name(INDEX),
+                                                           values.as_ref().clone().with_name(VALUE)])?;
+                    entries_builder.append_series(&entries.into_series())?;
+                },
+                _ => entries_builder.append_null()
+            }
+        }
+    }
+
+    Ok(entries_builder.finish().into_series())
+}
+
+/// Builds a single sparse vector from (index, value) pairs, combining duplicated indices.
+fn impl_from_coo<T>(indices_ca: &IdxCa, values_ca: &ChunkedArray<T>, dim: IdxSize, dup_policy: DupPolicy) -> PolarsResult<(IdxCa, ListChunked, ListChunked)>
+    where
+        T: PolarsNumericType,
+{
+    let mut pairs: Vec<(IdxSize, T::Native)> = Vec::with_capacity(indices_ca.len());
+    for (idx, value) in indices_ca.into_iter().zip(values_ca) {
+        if let (Some(idx), Some(value)) = (idx, value) {
+            polars_ensure!(idx < dim, ComputeError: "index {} is out of bounds for sparse vector \
+                of dim {}.", idx, dim);
+            pairs.push((idx, value));
+        }
+    }
+
+    let mut out_indices: Vec<IdxSize> = Vec::with_capacity(pairs.len());
+    let mut out_values: Vec<T::Native> = Vec::with_capacity(pairs.len());
+    canonicalize_pairs::<T>(&mut pairs, dup_policy, &mut out_indices, &mut out_values);
+
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", 1, out_indices.len(), IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<T> = ListPrimitiveChunkedBuilder::new("", 1, out_values.len(), T::get_dtype());
+    indices_builder.append_slice(&out_indices);
+    values_builder.append_slice(&out_values);
+    let dim_ca = IdxCa::from_slice("", &[dim]);
+    Ok((dim_ca, indices_builder.finish(), values_builder.finish()))
+}
@@ -0,0 +1,80 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::export::arrow::array::ListArray;
+use polars::export::arrow::offset::OffsetsBuffer;
+use pyo3_polars::derive::polars_expr;
+use crate::sparse::{sparse_fields, sparse_values_dtype, DIM, INDICES};
+
+
+const INDPTR: &str = "indptr";
+const DATA: &str = "data";
+
+fn csr_buffers(input_fields: &[Field]) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    Ok(Field::new(field.name(),
+                  DataType::Struct(vec![Field::new(DIM, IDX_DTYPE),
+                                        Field::new(INDPTR, DataType::List(Box::new(DataType::Int64))),
+                                        Field::new(INDICES, DataType::List(Box::new(IDX_DTYPE))),
+                                        Field::new(DATA, DataType::List(Box::new(sparse_values_dtype(field)?)))])))
+}
+
+/// Flattens a sparse vector column into the indptr, indices and data buffers of a CSR matrix,
+/// sharing the indices and values buffers of a single chunk column instead of copying them.
+#[polars_expr(output_type_func=csr_buffers)]
+fn to_csr(inputs: &[Series]) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+
+    // every row of a matrix shares the same dim, null rows are empty rows
+    let mut dim: Option<IdxSize> = None;
+    for row_dim in dim_ca.into_iter().flatten() {
+        match dim {
+            Some(dim) => polars_ensure!(dim == row_dim, ComputeError: "sparse vectors dims are \
+                inconsistent, got {} and {}.", dim, row_dim),
+            None => dim = Some(row_dim),
+        }
+    }
+
+    let (indptr, indices_chunks) = flatten_list(&indices_ca);
+    let (values_indptr, values_chunks) = flatten_list(&values_ca);
+    polars_ensure!(indptr == values_indptr, ComputeError: "sparse vectors indices and values \
+        lengths differ.");
+    let indices_series = Series::try_from(("", indices_chunks))?;
+    let values_series = Series::try_from(("", values_chunks))?;
+
+    let dim_ca: IdxCa = [dim].into_iter().collect_ca(DIM);
+    let indptr_ca = single_row_list(INDPTR, Series::new("", indptr));
+    let indices_ca = single_row_list(INDICES, indices_series);
+    let data_ca = single_row_list(DATA, values_series);
+    let out = StructChunked::new(inputs[0].name(), &[dim_ca.into_series(), indptr_ca.into_series(),
+                                                     indices_ca.into_series(), data_ca.into_series()])?;
+    Ok(out.into_series())
+}
+
+/// Returns the row offsets of a list column, and its values buffers without copying them.
+fn flatten_list(list_ca: &ListChunked) -> (Vec<i64>, Vec<ArrayRef>) {
+    let mut indptr: Vec<i64> = Vec::with_capacity(list_ca.len() + 1);
+    let mut chunks: Vec<ArrayRef> = Vec::with_capacity(list_ca.chunks().len());
+    indptr.push(0);
+    let mut nnz: i64 = 0;
+    for arr in list_ca.downcast_iter() {
+        let offsets = arr.offsets();
+        for length in offsets.lengths() {
+            nnz += length as i64;
+            indptr.push(nnz);
+        }
+        let start = *offsets.first() as usize;
+        let end = *offsets.last() as usize;
+        chunks.push(arr.values().sliced(start, end - start));
+    }
+    (indptr, chunks)
+}
+
+/// Wraps a whole Series as the single row of a list column, sharing its buffer when it has a single chunk.
+fn single_row_list(name: &str, s: Series) -> ListChunked {
+    let values = s.rechunk().chunks()[0].clone();
+    let nnz = values.len() as i64;
+    // SAFETY: [0, nnz] is monotonically increasing and ends at the length of the values
+    let offsets = unsafe { OffsetsBuffer::new_unchecked(vec![0, nnz].into()) };
+    let arr = ListArray::<i64>::new(ListArray::<i64>::default_datatype(values.data_type().clone()), offsets, values, None);
+    ListChunked::with_chunk(name, arr)
+}
@@ -0,0 +1,157 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use std::collections::HashMap;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{float_sparse_vector, sparse_struct};
+
+
+#[derive(Deserialize)]
+struct FitVocabularyKwargs {
+    min_frequency: u32,
+    max_categories: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct HotEncodeKwargs {
+    unknown: String,
+}
+
+/// Handling of categories missing from the vocabulary.
+#[derive(Clone, Copy)]
+enum Unknown {
+    Bucket,
+    Ignore,
+    Error,
+}
+
+impl Unknown {
+    fn parse(unknown: &str) -> PolarsResult<Self> {
+        match unknown {
+            "bucket" => Ok(Unknown::Bucket),
+            "ignore" => Ok(Unknown::Ignore),
+            "error" => Ok(Unknown::Error),
+            unknown => polars_bail!(ComputeError: "'{}' is unsupported.", unknown),
+        }
+    }
+}
+
+fn vocabulary_list(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(input_fields[0].name(), DataType::List(Box::new(DataType::String))))
+}
+
+/// Collects the sorted categories occurring at least min_frequency times, keeping the max_categories most frequent.
+#[polars_expr(output_type_func=vocabulary_list)]
+fn fit_vocabulary(inputs: &[Series], kwargs: FitVocabularyKwargs) -> PolarsResult<Series> {
+    let categories_s = match inputs[0].dtype() {
+        DataType::String => inputs[0].clone(),
+        DataType::List(inner) if **inner == DataType::String => inputs[0].explode()?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for fit_vocabulary, expected String, List(String)."))
+        }
+    };
+
+    let mut counts: HashMap<&str, IdxSize> = HashMap::new();
+    for category in categories_s.str()?.into_iter().flatten() {
+        *counts.entry(category).or_insert(0) += 1;
+    }
+    let mut categories: Vec<(&str, IdxSize)> = counts.into_iter()
+        .filter(|(_, count)| *count >= kwargs.min_frequency as IdxSize)
+        .collect();
+    // most frequent first, ties broken by category so the cutoff is deterministic
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    if let Some(max_categories) = kwargs.max_categories {
+        categories.truncate(max_categories as usize);
+    }
+    let mut categories: Vec<&str> = categories.into_iter().map(|(category, _)| category).collect();
+    categories.sort_unstable();
+
+    Ok(Series::new(inputs[0].name(), categories).implode()?.into_series())
+}
+
+/// Encodes every category by its position in the vocabulary, unknown categories go to the bucket right after it.
+#[polars_expr(output_type_func=float_sparse_vector)]
+fn hot_encode(inputs: &[Series], kwargs: HotEncodeKwargs) -> PolarsResult<Series> {
+    let unknown = Unknown::parse(&kwargs.unknown)?;
+    polars_ensure!(inputs[1].len() == 1, ComputeError: "expected a single vocabulary row, got {}.", inputs[1].len());
+    let vocabulary_s = inputs[1].list()?.get_as_series(0).unwrap_or_else(|| Series::new_empty("", &DataType::String));
+    let vocabulary_ca = vocabulary_s.str()?;
+    let mut vocabulary: HashMap<&str, IdxSize> = HashMap::with_capacity(vocabulary_ca.len());
+    for category in vocabulary_ca.into_iter().flatten() {
+        let position = vocabulary.len() as IdxSize;
+        vocabulary.entry(category).or_insert(position);
+    }
+    let n_categories = vocabulary.len() as IdxSize;
+    let dim = match unknown {
+        Unknown::Bucket => n_categories + 1,
+        _ => n_categories,
+    };
+    let encode = |category: &str| match vocabulary.get(category) {
+        Some(idx) => Ok(Some(*idx)),
+        None => match unknown {
+            Unknown::Bucket => Ok(Some(n_categories)),
+            Unknown::Ignore => Ok(None),
+            Unknown::Error => polars_bail!(ComputeError: "category '{}' is not in the vocabulary.", category),
+        },
+    };
+
+    let len = inputs[0].len();
+    let mut dim_vec: Vec<Option<IdxSize>> = Vec::with_capacity(len);
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new("", len, len, DataType::Float64);
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<f64> = Vec::new();
+
+    // a single category per row for one-hot, or a list of them for multi-hot, null rows stay null
+    let mut append_row = |categories: Option<Vec<Option<&str>>>| -> PolarsResult<()> {
+        match categories {
+            Some(categories) => {
+                out_indices.clear();
+                for category in categories.into_iter().flatten() {
+                    if let Some(idx) = encode(category)? {
+                        out_indices.push(idx);
+                    }
+                }
+                out_indices.sort_unstable();
+                out_indices.dedup();
+                out_values.clear();
+                out_values.resize(out_indices.len(), 1.0);
+                dim_vec.push(Some(dim));
+                indices_builder.append_slice(&out_indices);
+                values_builder.append_slice(&out_values);
+            },
+            None => {
+                dim_vec.push(None);
+                indices_builder.append_null();
+                values_builder.append_null();
+            }
+        }
+        Ok(())
+    };
+    match inputs[0].dtype() {
+        DataType::String => {
+            for category in inputs[0].str()?.into_iter() {
+                append_row(category.map(|category| vec![Some(category)]))?;
+            }
+        },
+        DataType::List(inner) if **inner == DataType::String => {
+            unsafe {
+                for categories in inputs[0].list()?.amortized_iter() {
+                    append_row(match &categories {
+                        Some(categories) => Some(categories.as_ref().str()?.into_iter().collect()),
+                        None => None,
+                    })?;
+                }
+            }
+        },
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for hot_encode, expected String, List(String)."))
+        }
+    }
+
+    let dim_ca: IdxCa = dim_vec.into_iter().collect_ca("");
+    sparse_struct(inputs[0].name(), dim_ca, indices_builder.finish(), values_builder.finish())
+}
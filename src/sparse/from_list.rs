@@ -1,39 +1,62 @@
 #![allow(clippy::unused_unit)]
 use pyo3_polars::derive::polars_expr;
-use pyo3_polars::export::polars_core::export::num::Signed;
 use polars::prelude::*;
-use num_traits::Zero;
+use num_traits::{ToPrimitive, Zero};
 use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
 use polars::chunked_array::builder::AnonymousListBuilder;
+use serde::Deserialize;
 use super::{DIM, INDICES, VALUES};
 
 
+#[derive(Deserialize)]
+struct FromListKwargs {
+    epsilon: f64,
+}
+
 fn sparse_vector(input_fields: &[Field]) -> PolarsResult<Field> {
     let field = &input_fields[0];
     match field.data_type() {
-        DataType::List(element) => {
+        DataType::List(element) | DataType::Array(element, _) => {
             Ok(Field::new(&field.name().clone(),
                           DataType::Struct(vec![Field::new(DIM, DataType::UInt32),
                                                 Field::new(INDICES, DataType::List(Box::new(DataType::UInt32))),
                                                 Field::new(VALUES, DataType::List(Box::new(*element.clone())))]
                           )))
         },
-        _ => unreachable!(),
+        dtype => polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for from_list, expected List or Array.")),
     }
 }
 
 #[polars_expr(output_type_func=sparse_vector)]
-fn from_list(inputs: &[Series]) -> PolarsResult<Series> {
-    let lst_ca = inputs[0].list()?;
+fn from_list(inputs: &[Series], kwargs: FromListKwargs) -> PolarsResult<Series> {
+    if kwargs.epsilon < 0.0 {
+        polars_bail!(ComputeError: "epsilon must be greater or equals to 0.")
+    }
+
+    // fixed width arrays are handled as lists, their width becomes the dimension
+    let lst_series = match inputs[0].dtype() {
+        DataType::Array(element, _) => inputs[0].cast(&DataType::List(element.clone()))?,
+        _ => inputs[0].clone(),
+    };
+    // only the true elements of a Boolean list are stored, handled as UInt8 ones until the output
+    let values_dtype = lst_series.list()?.inner_dtype();
+    let lst_series = match values_dtype {
+        DataType::Boolean => lst_series.cast(&DataType::List(Box::new(DataType::UInt8)))?,
+        _ => lst_series,
+    };
+    let lst_ca = lst_series.list()?;
     match lst_ca.inner_dtype() {
-        DataType::Int32 | DataType::Int64 | DataType::Float32 | DataType::Float64 => impl_from_list(lst_ca),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 |
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 |
+        DataType::Float32 | DataType::Float64 => impl_from_list(lst_ca, kwargs.epsilon, &values_dtype),
         dtype => {
             polars_bail!(InvalidOperation:format!("dtype {dtype} not \
-            supported for from_list, expected Int32, Int64, Float32, Float64."))
+            supported for from_list, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
         }
     }
 }
-fn impl_from_list(series_lst_ca: &ListChunked) -> PolarsResult<Series>{
+fn impl_from_list(series_lst_ca: &ListChunked, epsilon: f64, values_dtype: &DataType) -> PolarsResult<Series>{
     // holders for the dimension, indices list and values list
     let mut indices_builder:ListPrimitiveChunkedBuilder<UInt32Type> = ListPrimitiveChunkedBuilder::new(INDICES, series_lst_ca.len(), series_lst_ca.len(), DataType::UInt32);
     let mut values_lst: Vec<Option<Series>> = Vec::with_capacity(series_lst_ca.len());
@@ -44,27 +67,19 @@ fn impl_from_list(series_lst_ca: &ListChunked) -> PolarsResult<Series>{
                 let elements = &elements;
                 dim_vec.push(Some(elements.len() as IdxSize));
                 let (out_indices, out_values) = match series_lst_ca.inner_dtype() {
-                    DataType::Int32 => {
-                        let (out_indices, out_values) = impl_from_single_list_to_sparse(elements.i32().unwrap());
-                        (out_indices.into_series(), out_values.into_series())
-                    },
-                    DataType::Int64 => {
-                        let (out_indices, out_values) = impl_from_single_list_to_sparse(elements.i64().unwrap());
-                        (out_indices.into_series(), out_values.into_series())
-                    },
-                    DataType::Float32 => {
-                        let (out_indices, out_values) = impl_from_single_list_to_sparse(elements.f32().unwrap());
-                        (out_indices.into_series(), out_values.into_series())
-                    },
-                    DataType::Float64 => {
-                        let (out_indices, out_values) = impl_from_single_list_to_sparse(elements.f64().unwrap());
-                        (out_indices.into_series(), out_values.into_series())
-                    },
+                    DataType::Int8 => single_list_to_sparse(elements.i8().unwrap(), epsilon),
+                    DataType::Int16 => single_list_to_sparse(elements.i16().unwrap(), epsilon),
+                    DataType::Int32 => single_list_to_sparse(elements.i32().unwrap(), epsilon),
+                    DataType::Int64 => single_list_to_sparse(elements.i64().unwrap(), epsilon),
+                    DataType::UInt8 => single_list_to_sparse(elements.u8().unwrap(), epsilon),
+                    DataType::UInt16 => single_list_to_sparse(elements.u16().unwrap(), epsilon),
+                    DataType::UInt32 => single_list_to_sparse(elements.u32().unwrap(), epsilon),
+                    DataType::UInt64 => single_list_to_sparse(elements.u64().unwrap(), epsilon),
+                    DataType::Float32 => single_list_to_sparse(elements.f32().unwrap(), epsilon),
+                    DataType::Float64 => single_list_to_sparse(elements.f64().unwrap(), epsilon),
                     _ => unreachable!()
                 };
-                indices_builder.append_series(&out_indices).map_err(|err| {
-                    return err
-                })?;
+                indices_builder.append_series(&out_indices)?;
                 values_lst.push(Some(out_values));
             },
             None => {
@@ -81,14 +96,12 @@ fn impl_from_list(series_lst_ca: &ListChunked) -> PolarsResult<Series>{
     for value in &values_lst {
         match value {
             Some(value)=>{
-                values_builder.append_series(value).map_err(|err|{
-                    return err;
-                })?;
+                values_builder.append_series(value)?;
             },
             None => {values_builder.append_null();}
         };
     }
-    let values_series = values_builder.finish().into_series();
+    let values_series = values_builder.finish().into_series().cast(&DataType::List(Box::new(values_dtype.clone())))?;
     let out = StructChunked::new(series_lst_ca.name(),
                                  &[dim_series, indices_series, values_series]).unwrap();
 
@@ -96,22 +109,32 @@ fn impl_from_list(series_lst_ca: &ListChunked) -> PolarsResult<Series>{
 }
 
 #[inline]
-fn impl_from_single_list_to_sparse<T>(ca: &ChunkedArray<T>) -> (ChunkedArray<UInt32Type>, ChunkedArray<T>)
+fn single_list_to_sparse<T>(ca: &ChunkedArray<T>, epsilon: f64) -> (Series, Series)
     where
         T: PolarsNumericType,
-        T::Native: Signed,
+        T::Native: ToPrimitive,
+        ChunkedArray<T>: IntoSeries,
+{
+    let (out_indices, out_values) = impl_from_single_list_to_sparse(ca, epsilon);
+    (out_indices.into_series(), out_values.into_series())
+}
+
+#[inline]
+fn impl_from_single_list_to_sparse<T>(ca: &ChunkedArray<T>, epsilon: f64) -> (ChunkedArray<UInt32Type>, ChunkedArray<T>)
+    where
+        T: PolarsNumericType,
+        T::Native: ToPrimitive,
 {
     let mut indices_out: Vec<Option<u32>> = Vec::with_capacity(ca.len());
     let mut values_out: Vec<Option<T::Native>> = Vec::with_capacity(ca.len());
     for (idx, element) in ca.into_iter().enumerate() {
-        match element {
-            Some(val) => {
-                if !Zero::is_zero(&val){
-                    indices_out.push(Some(idx as IdxSize));
-                    values_out.push(Some(val));
-                }
-            },
-            None => ()
+        if let Some(val) = element {
+            // values within epsilon of zero are not stored
+            let is_zero = Zero::is_zero(&val) || T::Native::to_f64(&val).unwrap().abs() < epsilon;
+            if !is_zero {
+                indices_out.push(Some(idx as IdxSize));
+                values_out.push(Some(val));
+            }
         }
     }
 
@@ -119,4 +142,4 @@ fn impl_from_single_list_to_sparse<T>(ca: &ChunkedArray<T>) -> (ChunkedArray<UIn
     let values_out: ChunkedArray<T> = values_out.into_iter().collect_ca("");
 
     (indices_out, values_out)
-}
\ No newline at end of file
+}
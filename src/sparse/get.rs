@@ -0,0 +1,143 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{broadcast, numeric_values, restore_values, sparse_fields, sparse_values_dtype};
+
+
+#[derive(Deserialize)]
+struct GatherKwargs {
+    indices: Vec<u32>,
+}
+
+fn sparse_value(input_fields: &[Field]) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    Ok(Field::new(field.name(), sparse_values_dtype(field)?))
+}
+
+fn sparse_values_list(input_fields: &[Field]) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    Ok(Field::new(field.name(), DataType::List(Box::new(sparse_values_dtype(field)?))))
+}
+
+#[polars_expr(output_type_func=sparse_value)]
+fn get(inputs: &[Series]) -> PolarsResult<Series> {
+    let len = inputs[0].len().max(inputs[1].len());
+    let sparse = broadcast(&inputs[0], len)?;
+    let index_series = broadcast(&inputs[1], len)?.cast(&IDX_DTYPE)?;
+    let index_ca = index_series.idx()?;
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&sparse)?;
+    let values_dtype = values_ca.inner_dtype();
+    let values_ca = numeric_values(&values_ca)?;
+
+    let out = match values_ca.inner_dtype() {
+        DataType::Int8 => impl_get::<Int8Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::Int16 => impl_get::<Int16Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::Int32 => impl_get::<Int32Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::Int64 => impl_get::<Int64Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::UInt8 => impl_get::<UInt8Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::UInt16 => impl_get::<UInt16Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::UInt32 => impl_get::<UInt32Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::UInt64 => impl_get::<UInt64Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::Float32 => impl_get::<Float32Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        DataType::Float64 => impl_get::<Float64Type>(&dim_ca, &indices_ca, &values_ca, index_ca)?.into_series(),
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for get, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
+        }
+    };
+    Ok(out.cast(&values_dtype)?.with_name(inputs[0].name()))
+}
+
+#[polars_expr(output_type_func=sparse_values_list)]
+fn gather(inputs: &[Series], kwargs: GatherKwargs) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let positions = &kwargs.indices;
+    let values_dtype = values_ca.inner_dtype();
+    let values_ca = numeric_values(&values_ca)?;
+
+    let out = match values_ca.inner_dtype() {
+        DataType::Int8 => impl_gather::<Int8Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::Int16 => impl_gather::<Int16Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::Int32 => impl_gather::<Int32Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::Int64 => impl_gather::<Int64Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::UInt8 => impl_gather::<UInt8Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::UInt16 => impl_gather::<UInt16Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::UInt32 => impl_gather::<UInt32Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::UInt64 => impl_gather::<UInt64Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::Float32 => impl_gather::<Float32Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        DataType::Float64 => impl_gather::<Float64Type>(&dim_ca, &indices_ca, &values_ca, positions)?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for gather, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
+        }
+    };
+    Ok(restore_values(out, &values_dtype)?.with_name(inputs[0].name()).into_series())
+}
+
+fn impl_get<T>(dim_ca: &IdxCa, indices_ca: &ListChunked, values_ca: &ListChunked, index_ca: &IdxCa) -> PolarsResult<ChunkedArray<T>>
+    where
+        T: PolarsNumericType,
+{
+    let mut out: Vec<Option<T::Native>> = Vec::with_capacity(dim_ca.len());
+    unsafe {
+        for (((dim, indices), values), index) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter())
+            .zip(index_ca) {
+            match (dim, indices, values, index) {
+                (Some(dim), Some(indices), Some(values), Some(index)) => {
+                    let indices = indices.as_ref().idx()?;
+                    let values = values.as_ref().unpack::<T>()?;
+                    out.push(get_value(dim, indices.cont_slice()?, values, index));
+                },
+                _ => out.push(None)
+            }
+        }
+    }
+
+    Ok(out.into_iter().collect_ca(""))
+}
+
+fn impl_gather<T>(dim_ca: &IdxCa, indices_ca: &ListChunked, values_ca: &ListChunked, positions: &[u32]) -> PolarsResult<ListChunked>
+    where
+        T: PolarsNumericType,
+{
+    let mut gather_builder: ListPrimitiveChunkedBuilder<T> = ListPrimitiveChunkedBuilder::new("", dim_ca.len(), dim_ca.len() * positions.len(), T::get_dtype());
+    unsafe {
+        for ((dim, indices), values) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter()) {
+            match (dim, indices, values) {
+                (Some(dim), Some(indices), Some(values)) => {
+                    let indices = indices.as_ref().idx()?;
+                    let values = values.as_ref().unpack::<T>()?;
+                    let indices = indices.cont_slice()?;
+                    let gathered: Vec<Option<T::Native>> = positions.iter()
+                        .map(|index| get_value(dim, indices, values, *index))
+                        .collect();
+                    gather_builder.append_iter(gathered.into_iter());
+                },
+                _ => gather_builder.append_null()
+            }
+        }
+    }
+
+    Ok(gather_builder.finish())
+}
+
+/// Looks up a single position by binary search over the sorted indices, out of bounds positions are null.
+#[inline]
+fn get_value<T>(dim: IdxSize, indices: &[IdxSize], values: &ChunkedArray<T>, index: IdxSize) -> Option<T::Native>
+    where
+        T: PolarsNumericType,
+{
+    if index >= dim {
+        return None;
+    }
+    match indices.binary_search(&index) {
+        Ok(position) => values.get(position),
+        Err(_) => Some(T::Native::default()),
+    }
+}
@@ -0,0 +1,104 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{float_sparse_vector, sparse_struct};
+use crate::sparse::canonicalize::{canonicalize_pairs, DupPolicy};
+
+
+#[derive(Deserialize)]
+struct HashFeaturesKwargs {
+    n_features: u32,
+    alternate_sign: bool,
+    seed: u32,
+}
+
+#[polars_expr(output_type_func=float_sparse_vector)]
+fn hash_features(inputs: &[Series], kwargs: HashFeaturesKwargs) -> PolarsResult<Series> {
+    polars_ensure!(kwargs.n_features > 0, ComputeError: "n_features must be greater than zero.");
+    let tokens_lst_ca = inputs[0].list()?;
+    polars_ensure!(tokens_lst_ca.inner_dtype() == DataType::String, InvalidOperation: "dtype {} not \
+        supported for hash_features, expected List(String).", tokens_lst_ca.inner_dtype());
+
+    let len = tokens_lst_ca.len();
+    let mut dim_vec: Vec<Option<IdxSize>> = Vec::with_capacity(len);
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new("", len, len, DataType::Float64);
+    let mut pairs: Vec<(IdxSize, f64)> = Vec::new();
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<f64> = Vec::new();
+
+    unsafe {
+        for tokens in tokens_lst_ca.amortized_iter() {
+            match tokens {
+                Some(tokens) => {
+                    pairs.clear();
+                    for token in tokens.as_ref().str()?.into_iter().flatten() {
+                        let (idx, sign) = hash_token(token, kwargs.n_features, kwargs.seed);
+                        let value = if kwargs.alternate_sign { sign } else { 1.0 };
+                        pairs.push((idx, value));
+                    }
+                    canonicalize_pairs::<Float64Type>(&mut pairs, DupPolicy::Sum, &mut out_indices, &mut out_values);
+                    dim_vec.push(Some(kwargs.n_features));
+                    indices_builder.append_slice(&out_indices);
+                    values_builder.append_slice(&out_values);
+                },
+                None => {
+                    dim_vec.push(None);
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    let dim_ca: IdxCa = dim_vec.into_iter().collect_ca("");
+    sparse_struct(inputs[0].name(), dim_ca, indices_builder.finish(), values_builder.finish())
+}
+
+/// Maps a token to its index and sign, the same way scikit-learn's FeatureHasher does.
+#[inline]
+fn hash_token(token: &str, n_features: u32, seed: u32) -> (IdxSize, f64) {
+    let h = murmur3_32(token.as_bytes(), seed) as i32;
+    let idx = if h == i32::MIN {
+        (i32::MAX as u32 - (n_features - 1)) % n_features
+    } else {
+        h.unsigned_abs() % n_features
+    };
+    let sign = if h >= 0 { 1.0 } else { -1.0 };
+    (idx as IdxSize, sign)
+}
+
+/// MurmurHash3 x86 32 bit, stable across processes and platforms.
+fn murmur3_32(key: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let mut h = seed;
+    let mut chunks = key.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k: u32 = 0;
+        for (shift, byte) in tail.iter().enumerate() {
+            k ^= (*byte as u32) << (8 * shift);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+    }
+
+    h ^= key.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^= h >> 16;
+    h
+}
@@ -0,0 +1,155 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use std::fmt::Write;
+use crate::sparse::{broadcast, sparse_dtype, sparse_fields, sparse_struct};
+use crate::sparse::canonicalize::{canonicalize_pairs, DupPolicy};
+
+
+const LABEL: &str = "label";
+const QID: &str = "qid";
+const FEATURES: &str = "features";
+
+#[derive(Deserialize)]
+struct ParseLibsvmKwargs {
+    zero_based: bool,
+    n_features: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct FormatLibsvmKwargs {
+    zero_based: bool,
+}
+
+fn libsvm_row(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(input_fields[0].name(),
+                  DataType::Struct(vec![Field::new(LABEL, DataType::Float64),
+                                        Field::new(QID, DataType::Int64),
+                                        Field::new(FEATURES, sparse_dtype(DataType::Float64))])))
+}
+
+/// Parses LIBSVM / SVMlight lines of the form `label [qid:n] idx:val ... [# comment]`.
+#[polars_expr(output_type_func=libsvm_row)]
+fn parse_libsvm(inputs: &[Series], kwargs: ParseLibsvmKwargs) -> PolarsResult<Series> {
+    let lines_ca = inputs[0].str()?;
+    let len = lines_ca.len();
+    let mut labels: Vec<Option<f64>> = Vec::with_capacity(len);
+    let mut qids: Vec<Option<i64>> = Vec::with_capacity(len);
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new("", len, len, DataType::Float64);
+    let mut pairs: Vec<(IdxSize, f64)> = Vec::new();
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<f64> = Vec::new();
+    let mut max_index: Option<IdxSize> = None;
+
+    for (row, line) in lines_ca.into_iter().enumerate() {
+        // blank and comment only lines hold no sample
+        let content = line.map(|line| line.split('#').next().unwrap().trim()).unwrap_or("");
+        if content.is_empty() {
+            labels.push(None);
+            qids.push(None);
+            indices_builder.append_null();
+            values_builder.append_null();
+            continue;
+        }
+
+        let mut tokens = content.split_whitespace();
+        let label = tokens.next().unwrap();
+        let label: f64 = label.parse()
+            .map_err(|_| polars_err!(ComputeError: "invalid label '{}' at line {}.", label, row + 1))?;
+        let mut qid: Option<i64> = None;
+        pairs.clear();
+        for token in tokens {
+            let (key, value) = token.split_once(':')
+                .ok_or_else(|| polars_err!(ComputeError: "invalid feature '{}' at line {}.", token, row + 1))?;
+            if key == QID {
+                qid = Some(value.parse()
+                    .map_err(|_| polars_err!(ComputeError: "invalid qid '{}' at line {}.", value, row + 1))?);
+                continue;
+            }
+            let idx: IdxSize = key.parse()
+                .map_err(|_| polars_err!(ComputeError: "invalid index '{}' at line {}.", key, row + 1))?;
+            let value: f64 = value.parse()
+                .map_err(|_| polars_err!(ComputeError: "invalid value '{}' at line {}.", value, row + 1))?;
+            let idx = if kwargs.zero_based {
+                idx
+            } else {
+                polars_ensure!(idx > 0, ComputeError: "index 0 at line {} is invalid for one-based \
+                    indices.", row + 1);
+                idx - 1
+            };
+            pairs.push((idx, value));
+        }
+        canonicalize_pairs::<Float64Type>(&mut pairs, DupPolicy::Last, &mut out_indices, &mut out_values);
+        max_index = max_index.max(out_indices.last().copied());
+        labels.push(Some(label));
+        qids.push(qid);
+        indices_builder.append_slice(&out_indices);
+        values_builder.append_slice(&out_values);
+    }
+
+    // without an explicit number of features, the dim is inferred from the largest index seen
+    let dim = match (kwargs.n_features, max_index) {
+        (Some(n_features), Some(max_index)) => {
+            polars_ensure!(max_index < n_features, ComputeError: "index {} is out of bounds for \
+                n_features = {}.", max_index, n_features);
+            n_features
+        },
+        (Some(n_features), None) => n_features,
+        (None, max_index) => max_index.map_or(0, |max_index| max_index + 1),
+    };
+    let dim_ca: IdxCa = labels.iter().map(|label| label.map(|_| dim)).collect_ca("");
+    let features = sparse_struct(FEATURES, dim_ca, indices_builder.finish(), values_builder.finish())?;
+    let labels_ca: Float64Chunked = labels.into_iter().collect_ca(LABEL);
+    let qids_ca: Int64Chunked = qids.into_iter().collect_ca(QID);
+
+    let out = StructChunked::new(inputs[0].name(), &[labels_ca.into_series(), qids_ca.into_series(), features])?;
+    Ok(out.into_series())
+}
+
+/// Formats a label, a sparse vector and an optional qid as LIBSVM / SVMlight lines.
+#[polars_expr(output_type=String)]
+fn format_libsvm(inputs: &[Series], kwargs: FormatLibsvmKwargs) -> PolarsResult<Series> {
+    let len = inputs[1].len();
+    let labels_series = broadcast(&inputs[0], len)?.cast(&DataType::Float64)?;
+    let labels_ca = labels_series.f64()?;
+    let qids_series = match inputs.get(2) {
+        Some(qids) => broadcast(qids, len)?.cast(&DataType::Int64)?,
+        None => Series::full_null(QID, len, &DataType::Int64),
+    };
+    let qids_ca = qids_series.i64()?;
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[1])?;
+    let values_ca = values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let values_ca = values_ca.list()?;
+    let offset: IdxSize = if kwargs.zero_based { 0 } else { 1 };
+
+    let mut lines: Vec<Option<String>> = Vec::with_capacity(len);
+    unsafe {
+        for ((((label, qid), dim), indices), values) in labels_ca.into_iter()
+            .zip(qids_ca)
+            .zip(dim_ca.into_iter())
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter()) {
+            match (label, dim, indices, values) {
+                (Some(label), Some(_), Some(indices), Some(values)) => {
+                    let mut line = format!("{}", label);
+                    if let Some(qid) = qid {
+                        write!(line, " {}:{}", QID, qid).unwrap();
+                    }
+                    let indices = indices.as_ref().idx()?;
+                    let values = values.as_ref().f64()?;
+                    for (idx, value) in indices.into_no_null_iter().zip(values.into_no_null_iter()) {
+                        write!(line, " {}:{}", idx + offset, value).unwrap();
+                    }
+                    lines.push(Some(line));
+                },
+                _ => lines.push(None)
+            }
+        }
+    }
+
+    let out: StringChunked = lines.into_iter().collect_ca(inputs[1].name());
+    Ok(out.into_series())
+}
@@ -0,0 +1,100 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{broadcast, sparse_fields};
+
+
+#[derive(Deserialize)]
+struct MatmulKwargs {
+    k: Option<u32>,
+}
+
+fn dense_product(input_fields: &[Field], kwargs: MatmulKwargs) -> PolarsResult<Field> {
+    let dtype = match kwargs.k {
+        Some(k) => DataType::Array(Box::new(DataType::Float64), k as usize),
+        None => DataType::Float64,
+    };
+    Ok(Field::new(input_fields[0].name(), dtype))
+}
+
+/// Multiplies every sparse vector by a dense weight vector, or by a row-major dim x k weight matrix.
+#[polars_expr(output_type_func_with_kwargs=dense_product)]
+fn matmul(inputs: &[Series], kwargs: MatmulKwargs) -> PolarsResult<Series> {
+    let len = inputs[0].len().max(inputs[1].len());
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&broadcast(&inputs[0], len)?)?;
+    let values_ca = values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let values_ca = values_ca.list()?;
+    let weights_series = inputs[1].cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let weights_ca = weights_series.list()?;
+    let k = kwargs.k.unwrap_or(1) as usize;
+    polars_ensure!(k > 0, ComputeError: "k must be greater than zero.");
+
+    let mut out_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(inputs[0].name(), len, len * k, DataType::Float64);
+    let mut out = vec![0.0; k];
+    if weights_ca.len() == 1 {
+        // a single weight vector, usually a literal, is read once and shared by every row
+        let weights = match weights_ca.get_as_series(0) {
+            Some(weights) => Some(weights.f64()?.rechunk()),
+            None => None,
+        };
+        let weights = weights.as_ref().map(weights_slice).transpose()?;
+        unsafe {
+            for ((dim, indices), values) in dim_ca.into_iter()
+                .zip(indices_ca.amortized_iter())
+                .zip(values_ca.amortized_iter()) {
+                match (dim, indices, values, weights) {
+                    (Some(dim), Some(indices), Some(values), Some(weights)) => {
+                        multiply_row(dim, indices.as_ref(), values.as_ref(), weights, k, &mut out)?;
+                        out_builder.append_slice(&out);
+                    },
+                    _ => out_builder.append_null()
+                }
+            }
+        }
+    } else {
+        let weights_series = broadcast(&weights_series, len)?;
+        let weights_ca = weights_series.list()?;
+        unsafe {
+            for (((dim, indices), values), weights) in dim_ca.into_iter()
+                .zip(indices_ca.amortized_iter())
+                .zip(values_ca.amortized_iter())
+                .zip(weights_ca.amortized_iter()) {
+                match (dim, indices, values, weights) {
+                    (Some(dim), Some(indices), Some(values), Some(weights)) => {
+                        let weights = weights.as_ref().f64()?.rechunk();
+                        multiply_row(dim, indices.as_ref(), values.as_ref(), weights_slice(&weights)?, k, &mut out)?;
+                        out_builder.append_slice(&out);
+                    },
+                    _ => out_builder.append_null()
+                }
+            }
+        }
+    }
+
+    let out = out_builder.finish().into_series();
+    match kwargs.k {
+        Some(k) => out.cast(&DataType::Array(Box::new(DataType::Float64), k as usize)),
+        None => out.explode(),
+    }
+}
+
+fn weights_slice(weights: &Float64Chunked) -> PolarsResult<&[f64]> {
+    weights.cont_slice().map_err(|_| polars_err!(ComputeError: "weights must not contain nulls."))
+}
+
+/// Writes the product of a sparse row and the row-major dim x k weights into out.
+fn multiply_row(dim: IdxSize, indices: &Series, values: &Series, weights: &[f64], k: usize, out: &mut [f64]) -> PolarsResult<()> {
+    polars_ensure!(weights.len() == dim as usize * k, ComputeError: "expected {} weights \
+        for sparse vector of dim {} and k = {}, got {}.", dim as usize * k, dim, k, weights.len());
+
+    out.iter_mut().for_each(|value| *value = 0.0);
+    for (idx, value) in indices.idx()?.into_no_null_iter().zip(values.f64()?.into_no_null_iter()) {
+        polars_ensure!(idx < dim, ComputeError: "index {} is out of bounds for \
+            sparse vector of dim {}.", idx, dim);
+        let row = &weights[idx as usize * k..(idx as usize + 1) * k];
+        out.iter_mut().zip(row).for_each(|(acc, weight)| *acc += value * weight);
+    }
+    Ok(())
+}
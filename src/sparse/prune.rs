@@ -0,0 +1,101 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use polars::chunked_array::builder::get_list_builder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{same_sparse_vector, sparse_fields, sparse_struct};
+
+
+#[derive(Deserialize)]
+struct TopKKwargs {
+    k: u32,
+    by: String,
+}
+
+#[derive(Deserialize)]
+struct ThresholdKwargs {
+    min_abs: f64,
+}
+
+/// Keeps the k largest entries of every row, by absolute value or by value.
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn top_k(inputs: &[Series], kwargs: TopKKwargs) -> PolarsResult<Series> {
+    let k = kwargs.k as usize;
+    let by_abs = match kwargs.by.as_str() {
+        "abs" => true,
+        "value" => false,
+        by => polars_bail!(ComputeError: "'{}' is unsupported.", by),
+    };
+
+    prune_sparse(&inputs[0], "top_k", |values, positions| {
+        positions.extend(0..values.len() as IdxSize);
+        if values.len() > k {
+            let key = |position: &IdxSize| {
+                let value = values[*position as usize];
+                if by_abs { value.abs() } else { value }
+            };
+            // stable sort, so ties keep the entry with the lower index
+            positions.sort_by(|a, b| key(b).total_cmp(&key(a)));
+            positions.truncate(k);
+            positions.sort_unstable();
+        }
+    })
+}
+
+/// Drops the entries whose absolute value is below min_abs.
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn threshold(inputs: &[Series], kwargs: ThresholdKwargs) -> PolarsResult<Series> {
+    let min_abs = kwargs.min_abs;
+
+    prune_sparse(&inputs[0], "threshold", |values, positions| {
+        positions.extend(values.iter().enumerate()
+            .filter(|(_, value)| value.abs() >= min_abs)
+            .map(|(position, _)| position as IdxSize));
+    })
+}
+
+/// Keeps the entries at the positions chosen from the row values, preserving their order and dtype.
+fn prune_sparse<F>(sparse: &Series, name: &str, choose: F) -> PolarsResult<Series>
+    where
+        F: Fn(&[f64], &mut Vec<IdxSize>),
+{
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(sparse)?;
+    let values_dtype = values_ca.inner_dtype();
+    polars_ensure!(values_dtype.is_numeric() || values_dtype == DataType::Boolean, InvalidOperation: "dtype {} not \
+        supported for {}, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean.",
+        values_dtype, name);
+    let float_values_ca = values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let float_values_ca = float_values_ca.list()?;
+
+    let len = dim_ca.len();
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder = get_list_builder(&values_dtype, len, len, "")?;
+    let mut row_values: Vec<f64> = Vec::new();
+    let mut positions: Vec<IdxSize> = Vec::new();
+
+    unsafe {
+        for ((indices, values), float_values) in indices_ca.amortized_iter()
+            .zip(values_ca.amortized_iter())
+            .zip(float_values_ca.amortized_iter()) {
+            match (indices, values, float_values) {
+                (Some(indices), Some(values), Some(float_values)) => {
+                    row_values.clear();
+                    row_values.extend(float_values.as_ref().f64()?.into_no_null_iter());
+                    positions.clear();
+                    choose(&row_values, &mut positions);
+
+                    let positions_ca = IdxCa::from_slice("", &positions);
+                    indices_builder.append_series(&indices.as_ref().take(&positions_ca)?)?;
+                    values_builder.append_series(&values.as_ref().take(&positions_ca)?)?;
+                },
+                _ => {
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    sparse_struct(sparse.name(), dim_ca, indices_builder.finish(), values_builder.finish())
+}
@@ -0,0 +1,120 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{sparse_fields, sparse_values_dtype};
+
+
+#[derive(Deserialize)]
+struct SummarizeHorizontalKwargs {
+    stat: String,
+    p: f64,
+}
+
+fn row_scalar(input_fields: &[Field], kwargs: SummarizeHorizontalKwargs) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    let dtype = match kwargs.stat.as_str() {
+        "nnz" | "arg_max" | "arg_min" => IDX_DTYPE,
+        "min" | "max" => sparse_values_dtype(field)?,
+        _ => DataType::Float64,
+    };
+    Ok(Field::new(field.name(), dtype))
+}
+
+#[polars_expr(output_type_func_with_kwargs=row_scalar)]
+fn summarize_horizontal(inputs: &[Series], kwargs: SummarizeHorizontalKwargs) -> PolarsResult<Series> {
+    let stat = kwargs.stat.as_str();
+    let p = kwargs.p;
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let out_dtype = match stat {
+        "nnz" | "arg_max" | "arg_min" => IDX_DTYPE,
+        "min" | "max" => values_ca.inner_dtype().clone(),
+        "sum" | "mean" | "norm" => DataType::Float64,
+        stat => polars_bail!(ComputeError: "'{}' is unsupported.", stat),
+    };
+    let values_ca = values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let values_ca = values_ca.list()?;
+
+    let mut out: Vec<Option<f64>> = Vec::with_capacity(dim_ca.len());
+    unsafe {
+        for ((dim, indices), values) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter()) {
+            match (dim, indices, values) {
+                (Some(dim), Some(indices), Some(values)) => {
+                    let indices = indices.as_ref().idx()?;
+                    let values: Vec<f64> = values.as_ref().f64()?.into_no_null_iter().collect();
+                    let has_implicit_zeros = (values.len() as IdxSize) < dim;
+                    let value = match stat {
+                        "nnz" => Some(values.len() as f64),
+                        "sum" => Some(values.iter().fold(0.0, |acc, value| acc + value)),
+                        "mean" => (dim > 0).then(|| values.iter().fold(0.0, |acc, value| acc + value) / dim as f64),
+                        "norm" => Some(lp_norm(&values, p)),
+                        "min" => extremum(&values, has_implicit_zeros, |value, other| value < other),
+                        "max" => extremum(&values, has_implicit_zeros, |value, other| value > other),
+                        "arg_min" => arg_extremum(indices.cont_slice()?, &values, dim, |value, other| value < other),
+                        "arg_max" => arg_extremum(indices.cont_slice()?, &values, dim, |value, other| value > other),
+                        _ => unreachable!()
+                    };
+                    out.push(value);
+                },
+                _ => out.push(None)
+            }
+        }
+    }
+
+    let out: Float64Chunked = out.into_iter().collect_ca(inputs[0].name());
+    out.into_series().cast(&out_dtype)
+}
+
+#[inline]
+pub(crate) fn lp_norm(values: &[f64], p: f64) -> f64 {
+    if p.is_infinite() {
+        values.iter().fold(0.0, |acc, value| acc.max(value.abs()))
+    } else {
+        values.iter().fold(0.0, |acc, value| acc + value.abs().powf(p)).powf(1.0 / p)
+    }
+}
+
+/// Returns the extremum of a row, where unstored positions count as zeros.
+#[inline]
+fn extremum<F>(values: &[f64], has_implicit_zeros: bool, is_better: F) -> Option<f64>
+    where
+        F: Fn(f64, f64) -> bool,
+{
+    let init = if has_implicit_zeros { Some(0.0) } else { None };
+    values.iter().fold(init, |acc, value| match acc {
+        Some(acc) if !is_better(*value, acc) => Some(acc),
+        _ => Some(*value),
+    })
+}
+
+/// Returns the first index holding the extremum of a row, as a dense arg_max / arg_min would.
+#[inline]
+fn arg_extremum<F>(indices: &[IdxSize], values: &[f64], dim: IdxSize, is_better: F) -> Option<f64>
+    where
+        F: Fn(f64, f64) -> bool,
+{
+    if dim == 0 {
+        return None;
+    }
+
+    let mut best: Option<(IdxSize, f64)> = None;
+    for (idx, value) in indices.iter().zip(values) {
+        if best.is_none_or(|(_, best_value)| is_better(*value, best_value)) {
+            best = Some((*idx, *value));
+        }
+    }
+
+    // the first unstored position is the first implicit zero of the row
+    if (indices.len() as IdxSize) < dim {
+        let first_zero = indices.iter().enumerate()
+            .find(|(position, idx)| **idx != *position as IdxSize)
+            .map_or(indices.len() as IdxSize, |(position, _)| position as IdxSize);
+        best = match best {
+            Some((idx, value)) if is_better(value, 0.0) || (value == 0.0 && idx < first_zero) => Some((idx, value)),
+            _ => Some((first_zero, 0.0)),
+        };
+    }
+    best.map(|(idx, _)| idx as f64)
+}
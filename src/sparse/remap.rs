@@ -0,0 +1,107 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use std::collections::HashMap;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{numeric_values, restore_values, same_sparse_vector, sparse_fields, sparse_struct};
+use crate::sparse::canonicalize::{canonicalize_pairs, DupPolicy};
+
+
+#[derive(Deserialize)]
+struct RemapKwargs {
+    old: Vec<u32>,
+    new: Vec<u32>,
+    dim: u32,
+    unmapped: String,
+}
+
+/// Moves every index to its new index in a space of the given dim, summing the values of indices mapped together.
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn remap(inputs: &[Series], kwargs: RemapKwargs) -> PolarsResult<Series> {
+    polars_ensure!(kwargs.old.len() == kwargs.new.len(), ComputeError: "mapping has {} old indices but {} \
+        new indices.", kwargs.old.len(), kwargs.new.len());
+    let drop_unmapped = match kwargs.unmapped.as_str() {
+        "drop" => true,
+        "error" => false,
+        unmapped => polars_bail!(ComputeError: "'{}' is unsupported.", unmapped),
+    };
+    let mut mapping: HashMap<IdxSize, IdxSize> = HashMap::with_capacity(kwargs.old.len());
+    for (old, new) in kwargs.old.into_iter().zip(kwargs.new) {
+        polars_ensure!(new < kwargs.dim, ComputeError: "new index {} is out of bounds for dim {}.", new, kwargs.dim);
+        if let Some(previous) = mapping.insert(old as IdxSize, new as IdxSize) {
+            polars_ensure!(previous == new as IdxSize, ComputeError: "index {} is mapped to both {} and {}.",
+                old, previous, new);
+        }
+    }
+    let map_index = |idx: IdxSize| match mapping.get(&idx) {
+        Some(new_idx) => Ok(Some(*new_idx)),
+        None if drop_unmapped => Ok(None),
+        None => polars_bail!(ComputeError: "index {} is not in the mapping.", idx),
+    };
+
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let values_dtype = values_ca.inner_dtype();
+    let dup_policy = DupPolicy::Sum.for_dtype(&values_dtype);
+    let values_ca = numeric_values(&values_ca)?;
+    let (indices_ca, values_ca) = match values_ca.inner_dtype() {
+        DataType::Int8 => impl_remap::<Int8Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::Int16 => impl_remap::<Int16Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::Int32 => impl_remap::<Int32Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::Int64 => impl_remap::<Int64Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::UInt8 => impl_remap::<UInt8Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::UInt16 => impl_remap::<UInt16Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::UInt32 => impl_remap::<UInt32Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::UInt64 => impl_remap::<UInt64Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::Float32 => impl_remap::<Float32Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        DataType::Float64 => impl_remap::<Float64Type, _>(&indices_ca, &values_ca, map_index, dup_policy)?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for remap, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
+        }
+    };
+
+    // null rows stay null, every other row moves to the new index space
+    let dim = kwargs.dim as IdxSize;
+    let dim_ca: IdxCa = dim_ca.into_iter().map(|row_dim| row_dim.map(|_| dim)).collect_ca("");
+    sparse_struct(inputs[0].name(), dim_ca, indices_ca, restore_values(values_ca, &values_dtype)?)
+}
+
+fn impl_remap<T, M>(indices_ca: &ListChunked, values_ca: &ListChunked, map_index: M, dup_policy: DupPolicy) -> PolarsResult<(ListChunked, ListChunked)>
+    where
+        T: PolarsNumericType,
+        M: Fn(IdxSize) -> PolarsResult<Option<IdxSize>>,
+{
+    let len = indices_ca.len();
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<T> = ListPrimitiveChunkedBuilder::new("", len, len, T::get_dtype());
+    let mut pairs: Vec<(IdxSize, T::Native)> = Vec::new();
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<T::Native> = Vec::new();
+
+    unsafe {
+        for (indices, values) in indices_ca.amortized_iter().zip(values_ca.amortized_iter()) {
+            match (indices, values) {
+                (Some(indices), Some(values)) => {
+                    let indices = indices.as_ref().idx()?;
+                    let values = values.as_ref().unpack::<T>()?;
+                    pairs.clear();
+                    for (idx, value) in indices.into_no_null_iter().zip(values.into_no_null_iter()) {
+                        if let Some(new_idx) = map_index(idx)? {
+                            pairs.push((new_idx, value));
+                        }
+                    }
+                    canonicalize_pairs::<T>(&mut pairs, dup_policy, &mut out_indices, &mut out_values);
+                    indices_builder.append_slice(&out_indices);
+                    values_builder.append_slice(&out_values);
+                },
+                _ => {
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    Ok((indices_builder.finish(), values_builder.finish()))
+}
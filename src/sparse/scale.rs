@@ -0,0 +1,149 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{float_sparse_vector, sparse_fields, sparse_struct};
+
+
+const SCALE: &str = "scale";
+const OFFSET: &str = "offset";
+const NNZ: &str = "nnz";
+
+#[derive(Deserialize)]
+struct ScaleKwargs {
+    unseen: String,
+}
+
+/// Handling of indices that were not stored in any row at fit time.
+#[derive(Clone, Copy)]
+enum Unseen {
+    Ignore,
+    Drop,
+    Error,
+}
+
+impl Unseen {
+    fn parse(unseen: &str) -> PolarsResult<Self> {
+        match unseen {
+            "ignore" => Ok(Unseen::Ignore),
+            "drop" => Ok(Unseen::Drop),
+            "error" => Ok(Unseen::Error),
+            unseen => polars_bail!(ComputeError: "'{}' is unsupported.", unseen),
+        }
+    }
+
+    #[inline]
+    fn apply(&self, idx: IdxSize, value: f64) -> PolarsResult<Option<f64>> {
+        match self {
+            Unseen::Ignore => Ok(Some(value)),
+            Unseen::Drop => Ok(None),
+            Unseen::Error => polars_bail!(ComputeError: "index {} was not seen at fit time.", idx),
+        }
+    }
+}
+
+/// Per index statistics of a fitted transform, kept as sorted indices and values.
+pub(crate) struct FittedVector {
+    indices: Vec<IdxSize>,
+    values: Vec<f64>,
+}
+
+impl FittedVector {
+    /// Reads the single sparse vector a fit step produced.
+    pub(crate) fn from_series(s: &Series) -> PolarsResult<Self> {
+        let (_, indices_ca, values_ca) = sparse_fields(s)?;
+        let values_ca = values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+        let indices = indices_ca.get_as_series(0).unwrap_or_else(|| Series::new_empty("", &IDX_DTYPE));
+        let values = values_ca.list()?.get_as_series(0).unwrap_or_else(|| Series::new_empty("", &DataType::Float64));
+        let mut pairs: Vec<(IdxSize, f64)> = indices.idx()?.into_no_null_iter()
+            .zip(values.f64()?.into_no_null_iter())
+            .collect();
+        pairs.sort_by_key(|(idx, _)| *idx);
+        let (indices, values) = pairs.into_iter().unzip();
+        Ok(FittedVector { indices, values })
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, idx: IdxSize) -> Option<f64> {
+        self.indices.binary_search(&idx).ok().map(|position| self.values[position])
+    }
+}
+
+/// Applies `(value - offset) / scale` to the stored values, with the fitted per index scale and offset.
+#[polars_expr(output_type_func=float_sparse_vector)]
+fn scale(inputs: &[Series], kwargs: ScaleKwargs) -> PolarsResult<Series> {
+    polars_ensure!(inputs[1].len() == 1, ComputeError: "expected a single fitted row, got {}.", inputs[1].len());
+    let fitted = inputs[1].struct_()?;
+    let scales = FittedVector::from_series(&fitted.field_by_name(SCALE)?)?;
+    let offsets = match fitted.field_by_name(OFFSET) {
+        Ok(offsets) => Some(FittedVector::from_series(&offsets)?),
+        Err(_) => None,
+    };
+    // indices stored at fit time, zero statistics are not kept in the scale vector itself
+    let seen = match fitted.field_by_name(NNZ) {
+        Ok(nnz) => Some(FittedVector::from_series(&nnz)?),
+        Err(_) => None,
+    };
+    let unseen = Unseen::parse(&kwargs.unseen)?;
+
+    impl_scale(&inputs[0], |idx, value| {
+        let is_seen = match &seen {
+            Some(seen) => seen.get(idx).is_some(),
+            None => scales.get(idx).is_some(),
+        };
+        if !is_seen {
+            return unseen.apply(idx, value);
+        }
+
+        // zero scales are treated as one, as scikit-learn does for constant features
+        let scale = match scales.get(idx) {
+            Some(scale) if scale != 0.0 => scale,
+            _ => 1.0,
+        };
+        let offset = offsets.as_ref().and_then(|offsets| offsets.get(idx)).unwrap_or(0.0);
+        Ok(Some((value - offset) / scale))
+    })
+}
+
+/// Maps the stored values of every row, entries mapped to None are dropped.
+pub(crate) fn impl_scale<F>(sparse: &Series, map_value: F) -> PolarsResult<Series>
+    where
+        F: Fn(IdxSize, f64) -> PolarsResult<Option<f64>>,
+{
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(sparse)?;
+    let values_ca = values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let values_ca = values_ca.list()?;
+    let len = dim_ca.len();
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new("", len, len, DataType::Float64);
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut out_values: Vec<f64> = Vec::new();
+
+    unsafe {
+        for (indices, values) in indices_ca.amortized_iter().zip(values_ca.amortized_iter()) {
+            match (indices, values) {
+                (Some(indices), Some(values)) => {
+                    out_indices.clear();
+                    out_values.clear();
+                    let indices = indices.as_ref().idx()?;
+                    let values = values.as_ref().f64()?;
+                    for (idx, value) in indices.into_no_null_iter().zip(values.into_no_null_iter()) {
+                        if let Some(value) = map_value(idx, value)? {
+                            out_indices.push(idx);
+                            out_values.push(value);
+                        }
+                    }
+                    indices_builder.append_slice(&out_indices);
+                    values_builder.append_slice(&out_values);
+                },
+                _ => {
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    sparse_struct(sparse.name(), dim_ca, indices_builder.finish(), values_builder.finish())
+}
@@ -0,0 +1,91 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use polars::chunked_array::builder::get_list_builder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{same_sparse_vector, sparse_fields, sparse_struct};
+
+
+#[derive(Deserialize)]
+struct SelectIndicesKwargs {
+    keep: Vec<u32>,
+    reindex: bool,
+}
+
+#[derive(Deserialize)]
+struct SliceKwargs {
+    start: u32,
+    end: Option<u32>,
+}
+
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn select_indices(inputs: &[Series], kwargs: SelectIndicesKwargs) -> PolarsResult<Series> {
+    let mut keep = kwargs.keep;
+    keep.sort_unstable();
+    keep.dedup();
+    let reindex = kwargs.reindex;
+
+    // kept indices are optionally compacted into 0..keep.len()
+    filter_sparse(&inputs[0],
+                  |dim| if reindex { keep.len() as IdxSize } else { dim },
+                  |idx| keep.binary_search(&idx).ok().map(|position| if reindex { position as IdxSize } else { idx }))
+}
+
+#[polars_expr(output_type_func=same_sparse_vector)]
+fn slice(inputs: &[Series], kwargs: SliceKwargs) -> PolarsResult<Series> {
+    let start = kwargs.start;
+    let end = kwargs.end;
+    polars_ensure!(end.is_none_or(|end| start <= end), ComputeError: "start must be lower or \
+        equals to end.");
+
+    filter_sparse(&inputs[0],
+                  |dim| end.unwrap_or(dim).min(dim).saturating_sub(start),
+                  |idx| (idx >= start && end.is_none_or(|end| idx < end)).then(|| idx - start))
+}
+
+/// Keeps the entries whose index is mapped to a new index, and sets the dim of every row.
+pub(crate) fn filter_sparse<D, M>(sparse: &Series, new_dim: D, map_index: M) -> PolarsResult<Series>
+    where
+        D: Fn(IdxSize) -> IdxSize,
+        M: Fn(IdxSize) -> Option<IdxSize>,
+{
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(sparse)?;
+    let len = dim_ca.len();
+    let mut dim_vec: Vec<Option<IdxSize>> = Vec::with_capacity(len);
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", len, len, IDX_DTYPE);
+    let mut values_builder = get_list_builder(&values_ca.inner_dtype(), len, len, "")?;
+    let mut out_indices: Vec<IdxSize> = Vec::new();
+    let mut positions: Vec<IdxSize> = Vec::new();
+
+    unsafe {
+        for ((dim, indices), values) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter()) {
+            match (dim, indices, values) {
+                (Some(dim), Some(indices), Some(values)) => {
+                    out_indices.clear();
+                    positions.clear();
+                    for (position, idx) in indices.as_ref().idx()?.into_no_null_iter().enumerate() {
+                        if let Some(new_idx) = map_index(idx) {
+                            out_indices.push(new_idx);
+                            positions.push(position as IdxSize);
+                        }
+                    }
+                    let positions_ca = IdxCa::from_slice("", &positions);
+                    dim_vec.push(Some(new_dim(dim)));
+                    indices_builder.append_slice(&out_indices);
+                    values_builder.append_series(&values.as_ref().take(&positions_ca)?)?;
+                },
+                _ => {
+                    dim_vec.push(None);
+                    indices_builder.append_null();
+                    values_builder.append_null();
+                }
+            }
+        }
+    }
+
+    let dim_ca: IdxCa = dim_vec.into_iter().collect_ca("");
+    sparse_struct(sparse.name(), dim_ca, indices_builder.finish(), values_builder.finish())
+}
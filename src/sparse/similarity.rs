@@ -0,0 +1,195 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use pyo3_polars::derive::polars_expr;
+use crate::sparse::{broadcast, check_schema_dims, sparse_fields, sparse_schema_dim};
+
+
+fn similarity_score(input_fields: &[Field]) -> PolarsResult<Field> {
+    check_schema_dims(input_fields)?;
+    // a fixed width dense side must match the dim of a compact sparse side
+    let schema_dim = input_fields.iter().find_map(|field| sparse_schema_dim(field.data_type()));
+    for field in input_fields {
+        if let (DataType::Array(_, width), Some(dim)) = (field.data_type(), schema_dim) {
+            polars_ensure!(*width == dim as usize, ShapeMismatch: "sparse vector of dim {} can not be \
+                combined with an array of width {}.", dim, width);
+        }
+    }
+    Ok(Field::new(input_fields[0].name(), DataType::Float64))
+}
+
+#[polars_expr(output_type_func=similarity_score)]
+fn dot(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_similarity(inputs, |dot, _, _| dot)
+}
+
+#[polars_expr(output_type_func=similarity_score)]
+fn cosine_similarity(inputs: &[Series]) -> PolarsResult<Series> {
+    impl_similarity(inputs, |dot, left_norm, right_norm| {
+        // zero vectors are orthogonal to everything, as in scikit-learn
+        if left_norm == 0.0 || right_norm == 0.0 {
+            0.0
+        } else {
+            dot / (left_norm.sqrt() * right_norm.sqrt())
+        }
+    })
+}
+
+/// Computes a score per row from the dot product and the squared L2 norms of both vectors.
+fn impl_similarity<F>(inputs: &[Series], score: F) -> PolarsResult<Series>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+{
+    let len = inputs[0].len().max(inputs[1].len());
+    let (left, right) = (&inputs[0], &inputs[1]);
+
+    // the dense side is not broadcast, so a single dense vector is read only once
+    let out = match (is_dense(left), is_dense(right)) {
+        (false, false) => sparse_sparse(&broadcast(left, len)?, &broadcast(right, len)?, score)?,
+        (false, true) => sparse_dense(&broadcast(left, len)?, right, score)?,
+        (true, false) => sparse_dense(&broadcast(right, len)?, left, score)?,
+        (true, true) => polars_bail!(InvalidOperation: "at least one of the inputs must be a sparse vector."),
+    };
+    Ok(out.with_name(inputs[0].name()).into_series())
+}
+
+#[inline]
+fn is_dense(s: &Series) -> bool {
+    matches!(s.dtype(), DataType::List(_) | DataType::Array(_, _))
+}
+
+fn float_values(values_ca: &ListChunked) -> PolarsResult<ListChunked> {
+    Ok(values_ca.cast(&DataType::List(Box::new(DataType::Float64)))?.list()?.clone())
+}
+
+fn sparse_sparse<F>(left: &Series, right: &Series, score: F) -> PolarsResult<Float64Chunked>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+{
+    let (left_dim_ca, left_indices_ca, left_values_ca) = sparse_fields(left)?;
+    let (right_dim_ca, right_indices_ca, right_values_ca) = sparse_fields(right)?;
+    let left_values_ca = float_values(&left_values_ca)?;
+    let right_values_ca = float_values(&right_values_ca)?;
+
+    let mut out: Vec<Option<f64>> = Vec::with_capacity(left_dim_ca.len());
+    unsafe {
+        let left_iter = left_dim_ca.into_iter()
+            .zip(left_indices_ca.amortized_iter())
+            .zip(left_values_ca.amortized_iter());
+        let right_iter = right_dim_ca.into_iter()
+            .zip(right_indices_ca.amortized_iter())
+            .zip(right_values_ca.amortized_iter());
+        for (((left_dim, left_indices), left_values), ((right_dim, right_indices), right_values)) in left_iter.zip(right_iter) {
+            match (left_dim, left_indices, left_values, right_dim, right_indices, right_values) {
+                (Some(left_dim), Some(left_indices), Some(left_values),
+                    Some(right_dim), Some(right_indices), Some(right_values)) => {
+                    polars_ensure!(left_dim == right_dim, ComputeError: "sparse vectors dims differ, \
+                        got {} and {}.", left_dim, right_dim);
+                    let left_indices = left_indices.as_ref().idx()?;
+                    let left_values = left_values.as_ref().f64()?;
+                    let right_indices = right_indices.as_ref().idx()?;
+                    let right_values = right_values.as_ref().f64()?;
+
+                    // sorted indices merge, only the intersection contributes to the dot product
+                    let mut dot = 0.0;
+                    let mut left_pairs = left_indices.into_no_null_iter().zip(left_values.into_no_null_iter()).peekable();
+                    let mut right_pairs = right_indices.into_no_null_iter().zip(right_values.into_no_null_iter()).peekable();
+                    while let (Some((left_idx, left_value)), Some((right_idx, right_value))) = (left_pairs.peek().copied(), right_pairs.peek().copied()) {
+                        if left_idx == right_idx {
+                            dot += left_value * right_value;
+                            left_pairs.next();
+                            right_pairs.next();
+                        } else if left_idx < right_idx {
+                            left_pairs.next();
+                        } else {
+                            right_pairs.next();
+                        }
+                    }
+                    let left_norm: f64 = left_values.into_no_null_iter().map(|value| value * value).sum();
+                    let right_norm: f64 = right_values.into_no_null_iter().map(|value| value * value).sum();
+                    out.push(Some(score(dot, left_norm, right_norm)));
+                },
+                _ => out.push(None)
+            }
+        }
+    }
+
+    Ok(out.into_iter().collect_ca(""))
+}
+
+fn sparse_dense<F>(sparse: &Series, dense: &Series, score: F) -> PolarsResult<Float64Chunked>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+{
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(sparse)?;
+    let values_ca = float_values(&values_ca)?;
+    let dense_s = dense.cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let dense_ca = dense_s.list()?;
+
+    let len = dim_ca.len();
+    let mut out: Vec<Option<f64>> = Vec::with_capacity(len);
+    if dense_ca.len() == 1 {
+        // a single dense vector, usually a literal, has its values and norm computed once for every row
+        let dense = match dense_ca.get_as_series(0) {
+            Some(dense_values) => Some(dense_row(&dense_values)?),
+            None => None,
+        };
+        unsafe {
+            for ((dim, indices), values) in dim_ca.into_iter()
+                .zip(indices_ca.amortized_iter())
+                .zip(values_ca.amortized_iter()) {
+                match (dim, indices, values, &dense) {
+                    (Some(dim), Some(indices), Some(values), Some((dense_values, dense_norm))) => {
+                        out.push(Some(sparse_dense_row(dim, indices.as_ref(), values.as_ref(), dense_values, *dense_norm, &score)?));
+                    },
+                    _ => out.push(None)
+                }
+            }
+        }
+    } else {
+        let dense_s = broadcast(&dense_s, len)?;
+        let dense_ca = dense_s.list()?;
+        unsafe {
+            for (((dim, indices), values), dense_values) in dim_ca.into_iter()
+                .zip(indices_ca.amortized_iter())
+                .zip(values_ca.amortized_iter())
+                .zip(dense_ca.amortized_iter()) {
+                match (dim, indices, values, dense_values) {
+                    (Some(dim), Some(indices), Some(values), Some(dense_values)) => {
+                        let (dense_values, dense_norm) = dense_row(dense_values.as_ref())?;
+                        out.push(Some(sparse_dense_row(dim, indices.as_ref(), values.as_ref(), &dense_values, dense_norm, &score)?));
+                    },
+                    _ => out.push(None)
+                }
+            }
+        }
+    }
+
+    Ok(out.into_iter().collect_ca(""))
+}
+
+/// Reads a dense vector with nulls as zeros, along with its squared L2 norm.
+fn dense_row(dense_values: &Series) -> PolarsResult<(Vec<f64>, f64)> {
+    let dense_values: Vec<f64> = dense_values.f64()?.iter()
+        .map(|value| value.unwrap_or(0.0))
+        .collect();
+    let dense_norm = dense_values.iter().map(|value| value * value).sum();
+    Ok((dense_values, dense_norm))
+}
+
+fn sparse_dense_row<F>(dim: IdxSize, indices: &Series, values: &Series, dense_values: &[f64], dense_norm: f64, score: &F) -> PolarsResult<f64>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+{
+    polars_ensure!(dense_values.len() == dim as usize, ComputeError: "dense vector of \
+        length {} does not match sparse vector of dim {}.", dense_values.len(), dim);
+
+    let mut dot = 0.0;
+    let mut sparse_norm = 0.0;
+    for (idx, value) in indices.idx()?.into_no_null_iter().zip(values.f64()?.into_no_null_iter()) {
+        polars_ensure!(idx < dim, ComputeError: "index {} is out of bounds for \
+            sparse vector of dim {}.", idx, dim);
+        dot += value * dense_values[idx as usize];
+        sparse_norm += value * value;
+    }
+    Ok(score(dot, sparse_norm, dense_norm))
+}
@@ -0,0 +1,83 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{float_sparse_vector, sparse_fields, sparse_struct};
+use crate::sparse::aggregate::sparse_summarize;
+use crate::sparse::scale::{impl_scale, FittedVector};
+
+
+const IDF: &str = "idf";
+
+#[derive(Deserialize)]
+struct FitIdfKwargs {
+    smooth_idf: bool,
+}
+
+#[derive(Deserialize)]
+struct TfidfKwargs {
+    sublinear_tf: bool,
+}
+
+/// Computes the inverse document frequency of every index, as scikit-learn's TfidfTransformer does.
+#[polars_expr(output_type_func=float_sparse_vector)]
+fn fit_idf(inputs: &[Series], kwargs: FitIdfKwargs) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let summary = sparse_summarize(&dim_ca, &indices_ca, &values_ca, 1.0)?;
+    let n_rows = summary.n_rows as f64;
+
+    let mut indices_builder: ListPrimitiveChunkedBuilder<IdxType> = ListPrimitiveChunkedBuilder::new("", 1, 1, IDX_DTYPE);
+    let mut values_builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new("", 1, 1, DataType::Float64);
+    let out_dim_ca: IdxCa = match summary.dim {
+        Some(dim) => {
+            let mut indices: Vec<IdxSize> = Vec::with_capacity(dim as usize);
+            let mut values: Vec<f64> = Vec::with_capacity(dim as usize);
+            for idx in 0..dim {
+                let df = summary.indices.get(&idx).map_or(0.0, |index_summary| index_summary.nnz as f64);
+                // without smoothing, indices that never occur have no finite idf
+                let idf = if kwargs.smooth_idf {
+                    ((1.0 + n_rows) / (1.0 + df)).ln() + 1.0
+                } else if df > 0.0 {
+                    (n_rows / df).ln() + 1.0
+                } else {
+                    continue;
+                };
+                indices.push(idx);
+                values.push(idf);
+            }
+            indices_builder.append_slice(&indices);
+            values_builder.append_slice(&values);
+            [Some(dim)].into_iter().collect_ca("")
+        },
+        None => {
+            indices_builder.append_null();
+            values_builder.append_null();
+            [None].into_iter().collect_ca("")
+        }
+    };
+    sparse_struct(inputs[0].name(), out_dim_ca, indices_builder.finish(), values_builder.finish())
+}
+
+/// Weights term counts by the fitted idf, when given, with optional sublinear scaling of the counts.
+#[polars_expr(output_type_func=float_sparse_vector)]
+fn tfidf(inputs: &[Series], kwargs: TfidfKwargs) -> PolarsResult<Series> {
+    let idf = match inputs.get(1) {
+        Some(fitted) => {
+            polars_ensure!(fitted.len() == 1, ComputeError: "expected a single fitted row, got {}.", fitted.len());
+            Some(FittedVector::from_series(&fitted.struct_()?.field_by_name(IDF)?)?)
+        },
+        None => None,
+    };
+
+    impl_scale(&inputs[0], |idx, value| {
+        let tf = if kwargs.sublinear_tf { value.ln() + 1.0 } else { value };
+        match &idf {
+            Some(idf) => match idf.get(idx) {
+                Some(idf) => Ok(Some(tf * idf)),
+                None => polars_bail!(ComputeError: "index {} has no fitted idf.", idx),
+            },
+            None => Ok(Some(tf)),
+        }
+    })
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::unused_unit)]
+use polars::prelude::*;
+use polars::chunked_array::builder::list::ListPrimitiveChunkedBuilder;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use crate::sparse::{numeric_values, restore_values, sparse_fields, sparse_schema_dim, sparse_values_dtype};
+
+
+#[derive(Deserialize)]
+struct ToDenseKwargs {
+    dim: Option<u32>,
+}
+
+fn dense_vector(input_fields: &[Field], kwargs: ToDenseKwargs) -> PolarsResult<Field> {
+    let field = &input_fields[0];
+    let values_dtype = sparse_values_dtype(field)?;
+    let dtype = match output_dim(field.data_type(), kwargs.dim)? {
+        Some(dim) => DataType::Array(Box::new(values_dtype), dim as usize),
+        None => DataType::List(Box::new(values_dtype)),
+    };
+    Ok(Field::new(field.name(), dtype))
+}
+
+/// The width of a fixed width output, given explicitly or carried in the schema of a compact sparse vector.
+fn output_dim(dtype: &DataType, dim: Option<u32>) -> PolarsResult<Option<IdxSize>> {
+    let dim = dim.map(|dim| dim as IdxSize);
+    match (sparse_schema_dim(dtype), dim) {
+        (Some(schema_dim), Some(dim)) => {
+            polars_ensure!(schema_dim == dim, ShapeMismatch: "sparse vector of dim {} can not be \
+                converted to an array of width {}.", schema_dim, dim);
+            Ok(Some(dim))
+        },
+        (schema_dim, dim) => Ok(schema_dim.or(dim)),
+    }
+}
+
+#[polars_expr(output_type_func_with_kwargs=dense_vector)]
+fn to_dense(inputs: &[Series], kwargs: ToDenseKwargs) -> PolarsResult<Series> {
+    let (dim_ca, indices_ca, values_ca) = sparse_fields(&inputs[0])?;
+    let dim = output_dim(inputs[0].dtype(), kwargs.dim)?;
+
+    // fixed width output requires every row to share the requested dimension
+    if let Some(dim) = dim {
+        for row_dim in dim_ca.into_iter().flatten() {
+            polars_ensure!(row_dim == dim, ComputeError: "sparse vector of dim {} can not be \
+                converted to an array of width {}.", row_dim, dim);
+        }
+    }
+
+    let values_dtype = values_ca.inner_dtype();
+    let values_ca = numeric_values(&values_ca)?;
+    let out = match values_ca.inner_dtype() {
+        DataType::Int8 => impl_to_dense::<Int8Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::Int16 => impl_to_dense::<Int16Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::Int32 => impl_to_dense::<Int32Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::Int64 => impl_to_dense::<Int64Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::UInt8 => impl_to_dense::<UInt8Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::UInt16 => impl_to_dense::<UInt16Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::UInt32 => impl_to_dense::<UInt32Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::UInt64 => impl_to_dense::<UInt64Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::Float32 => impl_to_dense::<Float32Type>(&dim_ca, &indices_ca, &values_ca)?,
+        DataType::Float64 => impl_to_dense::<Float64Type>(&dim_ca, &indices_ca, &values_ca)?,
+        dtype => {
+            polars_bail!(InvalidOperation:format!("dtype {dtype} not \
+            supported for to_dense, expected Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, UInt64, Float32, Float64, Boolean."))
+        }
+    };
+    let out = restore_values(out, &values_dtype)?.with_name(inputs[0].name()).into_series();
+
+    match dim {
+        Some(dim) => out.cast(&DataType::Array(Box::new(values_dtype), dim as usize)),
+        None => Ok(out),
+    }
+}
+
+fn impl_to_dense<T>(dim_ca: &IdxCa, indices_ca: &ListChunked, values_ca: &ListChunked) -> PolarsResult<ListChunked>
+    where
+        T: PolarsNumericType,
+{
+    let mut dense_builder: ListPrimitiveChunkedBuilder<T> = ListPrimitiveChunkedBuilder::new("", values_ca.len(), values_ca.len(), T::get_dtype());
+    unsafe {
+        for ((dim, indices_series), values_series) in dim_ca.into_iter()
+            .zip(indices_ca.amortized_iter())
+            .zip(values_ca.amortized_iter()) {
+            match (dim, indices_series, values_series) {
+                (Some(dim), Some(indices_series), Some(values_series)) => {
+                    let indices_ca = indices_series.as_ref().idx()?;
+                    let values_ca = values_series.as_ref().unpack::<T>()?;
+                    let mut dense = vec![T::Native::default(); dim as usize];
+                    for (idx, value) in indices_ca.into_iter().zip(values_ca) {
+                        if let (Some(idx), Some(value)) = (idx, value) {
+                            polars_ensure!(idx < dim, ComputeError: "index {} is out of bounds for \
+                                sparse vector of dim {}.", idx, dim);
+                            dense[idx as usize] = value;
+                        }
+                    }
+                    dense_builder.append_slice(&dense);
+                },
+                _ => dense_builder.append_null()
+            }
+        }
+    }
+
+    Ok(dense_builder.finish())
+}
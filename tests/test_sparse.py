@@ -0,0 +1,254 @@
+import polars as pl
+import pytest
+import polars_ml as plm
+
+
+def test_from_list_drops_zeros():
+    df = pl.DataFrame({'x': [[0.0, 1.5, 0.0, -2.0], [0.0, 0.0, 0.0, 0.0], None, [3.0, 0.0, 0.0, 1.0]]})
+
+    out = df.select(plm.sparse.from_list(pl.col('x')))
+
+    assert out['x'].struct.field('dim').to_list() == [4, 4, None, 4]
+    assert out['x'].struct.field('indices').to_list() == [[1, 3], [], None, [0, 3]]
+    assert out['x'].struct.field('values').to_list() == [[1.5, -2.0], [], None, [3.0, 1.0]]
+
+
+def test_from_list_to_dense_round_trip():
+    df = pl.DataFrame({'x': [[0.0, 1.5, 0.0, -2.0], [0.0, 0.0, 0.0, 0.0], None, [3.0, 0.0, 0.0, 1.0]]})
+
+    out = df.select(plm.sparse.to_dense(plm.sparse.from_list(pl.col('x'))))
+
+    assert out['x'].to_list() == df['x'].to_list()
+
+
+def test_arithmetic_merges_implicit_zeros():
+    df = pl.DataFrame({
+        'a': [[1.0, 0.0, 2.0, 0.0]],
+        'b': [[0.0, 3.0, -2.0, 0.0]]
+    }).select(plm.sparse.from_list(pl.col('a')), plm.sparse.from_list(pl.col('b')))
+
+    out = df.select(
+        plm.sparse.add(pl.col('a'), pl.col('b')).alias('add'),
+        plm.sparse.sub(pl.col('a'), pl.col('b')).alias('sub'),
+        plm.sparse.multiply(pl.col('a'), pl.col('b')).alias('multiply'),
+        plm.sparse.maximum(pl.col('a'), pl.col('b')).alias('maximum'),
+        plm.sparse.minimum(pl.col('a'), pl.col('b')).alias('minimum')
+    )
+
+    # the entries cancelling out are dropped, not kept as explicit zeros
+    expected = {
+        'add': ([0, 1], [1.0, 3.0]),
+        'sub': ([0, 1, 2], [1.0, -3.0, 4.0]),
+        'multiply': ([2], [-4.0]),
+        'maximum': ([0, 1, 2], [1.0, 3.0, 2.0]),
+        'minimum': ([2], [-2.0])
+    }
+    for name, (indices, values) in expected.items():
+        assert out[name].struct.field('indices').to_list() == [indices], name
+        assert out[name].struct.field('values').to_list() == [values], name
+
+
+def test_sub_keeps_unsigned_dtype():
+    df = pl.DataFrame({'a': [[1, 0, 5]], 'b': [[3, 0, 2]]}, schema={'a': pl.List(pl.UInt64), 'b': pl.List(pl.UInt64)}) \
+        .select(plm.sparse.from_list(pl.col('a')), plm.sparse.from_list(pl.col('b')))
+
+    out = df.select(plm.sparse.sub(pl.col('a'), pl.col('b')))
+
+    # the same as polars, u64 - u64 stays u64 and wraps around on overflow
+    assert out['a'].struct.field('values').dtype == pl.List(pl.UInt64)
+    assert out['a'].struct.field('values').to_list() == [[2 ** 64 - 2, 3]]
+
+
+def _normalize_frame():
+    return pl.DataFrame({
+        'g': ['a', 'b', 'a', 'b'],
+        'x': [[3.0, 0.0, 1.0], [0.0, 2.0, 0.0], [4.0, 0.0, 0.0], [0.0, 1.0, 5.0]]
+    }).with_columns(plm.sparse.from_list(pl.col('x')))
+
+
+def _dense(series):
+    return series.to_frame('x').select(plm.sparse.to_dense(pl.col('x')))['x'].to_list()
+
+
+def test_normalize_vertical_streaming():
+    df = _normalize_frame()
+
+    eager = df.select(plm.sparse.normalize(pl.col('x')))
+    streaming = df.lazy().select(plm.sparse.normalize(pl.col('x'))).collect(streaming=True)
+
+    # the norms are still fitted over the whole column, not over each streamed batch
+    assert _dense(streaming['x']) == _dense(eager['x'])
+
+
+def test_normalize_vertical_group_by():
+    df = _normalize_frame()
+
+    out = df.group_by('g', maintain_order=True).agg(plm.sparse.normalize(pl.col('x')))
+
+    # the norms are fitted per group, the same as normalizing each group on its own
+    for g, x in zip(out['g'], out['x']):
+        expected = df.filter(pl.col('g') == g).select(plm.sparse.normalize(pl.col('x')))['x']
+        assert _dense(x) == _dense(expected), g
+
+
+def test_normalize_vertical_over():
+    df = _normalize_frame()
+
+    out = df.select(plm.sparse.normalize(pl.col('x')).over('g'))
+
+    # the norms are fitted per group, and every row keeps its place
+    expected = df.with_row_index() \
+        .group_by('g', maintain_order=True) \
+        .map_groups(lambda group: group.with_columns(plm.sparse.normalize(pl.col('x')))) \
+        .sort('index')
+    assert _dense(out['x']) == _dense(expected['x'])
+    assert _dense(out['x'])[0] == [3.0 / 5.0, 0.0, 1.0]
+
+
+def test_arg_max_all_negative_row():
+    df = pl.DataFrame({'x': [[-1.0, 0.0, -3.0, 0.0], [-2.0, -1.0]]}).select(plm.sparse.from_list(pl.col('x')))
+
+    out = df.select(
+        plm.sparse.max(pl.col('x'), how='horizontal').alias('max'),
+        plm.sparse.arg_max(pl.col('x')).alias('arg_max'),
+        plm.sparse.arg_min(pl.col('x')).alias('arg_min')
+    )
+
+    # the implicit zeros beat every stored value, and the first of them is the arg_max
+    assert out['max'].to_list() == [0.0, -1.0]
+    assert out['arg_max'].to_list() == [1, 1]
+    assert out['arg_min'].to_list() == [2, 0]
+
+
+def test_hash_features_known_index():
+    df = pl.DataFrame({'tokens': [['hello', 'hello']]})
+
+    out = df.select(plm.sparse.hash_features(pl.col('tokens'), n_features=2 ** 20))
+
+    # murmurhash3_32('hello', seed=0) is 613153351
+    assert out['tokens'].struct.field('indices').to_list() == [[613153351 % 2 ** 20]]
+    assert out['tokens'].struct.field('values').to_list() == [[2.0]]
+
+
+@pytest.mark.parametrize('alternate_sign', [True, False])
+def test_hash_features_matches_sklearn(alternate_sign):
+    feature_extraction = pytest.importorskip('sklearn.feature_extraction')
+
+    tokens = [['hello', 'world', 'hello'], ['polars', 'sparse', 'vector', 'hashing'], []]
+    n_features = 2 ** 20
+    df = pl.DataFrame({'tokens': tokens})
+
+    out = df.select(plm.sparse.hash_features(pl.col('tokens'), n_features=n_features,
+                                             alternate_sign=alternate_sign))
+
+    hasher = feature_extraction.FeatureHasher(n_features=n_features, input_type='string',
+                                              alternate_sign=alternate_sign)
+    expected = hasher.transform(tokens)
+    expected.sort_indices()
+    for row in range(len(tokens)):
+        start, end = expected.indptr[row], expected.indptr[row + 1]
+        assert out['tokens'].struct.field('indices')[row].to_list() == expected.indices[start:end].tolist()
+        assert out['tokens'].struct.field('values')[row].to_list() == expected.data[start:end].tolist()
+
+
+@pytest.mark.parametrize('smooth_idf', [True, False])
+@pytest.mark.parametrize('sublinear_tf', [True, False])
+@pytest.mark.parametrize('norm', ['l1', 'l2', None])
+def test_tfidf_matches_sklearn(smooth_idf, sublinear_tf, norm):
+    text = pytest.importorskip('sklearn.feature_extraction.text')
+
+    # every term is seen at least once, so the idf is finite without smoothing as well
+    counts = [[3.0, 0.0, 1.0, 0.0], [2.0, 1.0, 0.0, 0.0], [0.0, 0.0, 4.0, 2.0], [0.0, 0.0, 0.0, 0.0]]
+    df = pl.DataFrame({'x': counts}).select(plm.sparse.from_list(pl.col('x')))
+
+    out = df.select(plm.sparse.tfidf(pl.col('x'), norm=norm, smooth_idf=smooth_idf, sublinear_tf=sublinear_tf))
+
+    transformer = text.TfidfTransformer(norm=norm, smooth_idf=smooth_idf, sublinear_tf=sublinear_tf)
+    expected = transformer.fit_transform(counts).toarray().tolist()
+    for row, expected_row in zip(_dense(out['x']), expected):
+        assert row == pytest.approx(expected_row)
+
+
+def test_remap_infers_dim():
+    df = pl.DataFrame({'x': [[1.0, 2.0, 0.0, 3.0]]}).select(plm.sparse.from_list(pl.col('x')))
+
+    out = df.select(plm.sparse.remap(pl.col('x'), {0: 2, 1: 2, 3: 0}, dim=None))
+
+    # without a dim, the new space ends right after the largest new index
+    assert out['x'].struct.field('dim').to_list() == [3]
+    assert out.select(plm.sparse.to_dense(pl.col('x')))['x'].to_list() == [[3.0, 0.0, 3.0]]
+
+
+def test_concat_all_null_block_with_dims():
+    df = pl.DataFrame({
+        'a': pl.Series([None, None], dtype=pl.List(pl.Float64)),
+        'b': [[1.0, 0.0], [0.0, 2.0]]
+    }).select(plm.sparse.from_list(pl.col('a')), plm.sparse.from_list(pl.col('b')))
+
+    out = df.select(plm.sparse.concat([pl.col('a'), pl.col('b')], dims=[3, None]).alias('x'))
+
+    # the null rows of the first block are 3 zeros, whatever the batch holds
+    assert out.select(plm.sparse.to_dense(pl.col('x')))['x'].to_list() == [[0.0, 0.0, 0.0, 1.0, 0.0],
+                                                                          [0.0, 0.0, 0.0, 0.0, 2.0]]
+
+
+def test_namespace_rejects_non_method_functions():
+    with pytest.raises(AttributeError):
+        pl.col('index').sparse.from_coo(pl.col('value'), dim=4)
+    with pytest.raises(AttributeError):
+        pl.col('a').sparse.concat([pl.col('b')])
+
+
+def test_matmul_rejects_k_not_matching_weights():
+    np = pytest.importorskip('numpy')
+
+    with pytest.raises(ValueError):
+        plm.sparse.matmul(pl.col('x'), np.ones((4, 2)), k=3)
+
+
+def test_to_scipy_matches_dense_rows():
+    pytest.importorskip('scipy')
+
+    series = pl.DataFrame({'x': [[0.0, 1.5, 0.0, -2.0], [0.0, 0.0, 0.0, 0.0], [3.0, 0.0, 0.0, 1.0]]}) \
+        .select(plm.sparse.from_list(pl.col('x')))['x']
+
+    matrix = plm.sparse.to_scipy(series)
+
+    assert matrix.shape == (3, 4)
+    assert matrix.toarray().tolist() == [[0.0, 1.5, 0.0, -2.0], [0.0, 0.0, 0.0, 0.0], [3.0, 0.0, 0.0, 1.0]]
+
+
+@pytest.mark.parametrize('zero_based', [True, False])
+@pytest.mark.parametrize('query_id', [None, 'qid'])
+def test_libsvm_round_trip(tmp_path, zero_based, query_id):
+    df = pl.DataFrame({
+        'label': [1.0, -1.0, 0.5],
+        'qid': [3, 3, 7],
+        'features': [[0.0, 1.5, 0.0, -2.0], [0.0, 0.0, 0.0, 0.0], [3.0, 0.0, 0.0, 1.0]]
+    }).with_columns(plm.sparse.from_list(pl.col('features')))
+    path = tmp_path / 'data.svm'
+
+    plm.sparse.write_libsvm(df, path, query_id=query_id, zero_based=zero_based)
+    out = plm.sparse.read_libsvm(path, zero_based=zero_based, n_features=4, query_id=query_id is not None)
+
+    # the index written first is 0 for zero based files and 1 otherwise
+    first_index = path.read_text().splitlines()[0].split()[-2].split(':')[0]
+    assert first_index == ('1' if zero_based else '2')
+    assert out['label'].to_list() == df['label'].to_list()
+    if query_id is None:
+        assert 'qid' not in out.columns
+    else:
+        assert out['qid'].to_list() == df['qid'].to_list()
+    assert out.select(plm.sparse.to_dense(pl.col('features')))['features'].to_list() == \
+        df.select(plm.sparse.to_dense(pl.col('features')))['features'].to_list()
+
+
+def test_add_compact_dims_mismatch_at_plan_time():
+    lf = pl.DataFrame({'a': [[1.0, 0.0, 2.0]], 'b': [[0.0, 3.0, 0.0, 4.0]]}) \
+        .select(plm.sparse.from_list(pl.col('a')), plm.sparse.from_list(pl.col('b'))) \
+        .lazy() \
+        .select(plm.sparse.to_compact(pl.col('a'), 3), plm.sparse.to_compact(pl.col('b'), 4))
+
+    # the dims are part of the compact dtype, so resolving the schema is enough to fail
+    with pytest.raises(pl.exceptions.PolarsError, match='sparse vectors of dims 3 and 4 can not be combined'):
+        lf.select(plm.sparse.add(pl.col('a'), pl.col('b'))).schema